@@ -1,13 +1,57 @@
 use crate::config::Config;
+use crate::events::{Waker, WakingSender, Worker};
+use crate::files::{self, FileTree};
+use crate::follow::{self, FollowMessage};
 use crate::github::GitHubFetcher;
-use crate::markdown::{Document, SyntaxHighlighter};
-use crate::storage::{Bookmarks, History};
-use crate::theme::Theme;
+use crate::reload::{self, ChangeHighlight, LineDiff};
+use crate::storage::{Bookmarks, History, SearchHistory};
+use crate::syntaxes;
+use crate::watch::{self, FileChange, FileWatcher};
+use barkdocs::export::{self, ExportFormat};
+use barkdocs::markdown::{Document, SyntaxHighlighter};
+use barkdocs::outline;
+use barkdocs::search::{
+    self, HitSource, ProjectSearchMessage, SearchHit, SearchMatch, SearchOptions,
+};
+use barkdocs::theme::Theme;
+use barkdocs::view::{self, MarkdownViewState};
 use ratatui::layout::Rect;
-use std::path::PathBuf;
+use ratatui::widgets::{Paragraph, Wrap};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Receiver;
 use std::time::SystemTime;
-use tui_textarea::TextArea;
+use tui_textarea::{CursorMove, TextArea};
+
+/// Maximum number of entries shown in the file picker
+const MAX_PICKER_ENTRIES: usize = 500;
+
+/// Placeholder path for a document read from standard input
+pub const STDIN_PATH: &str = "<stdin>";
+
+/// Bytes of a document parsed for an overlay preview
+const PREVIEW_MAX_BYTES: usize = 32 * 1024;
+
+/// Rendered lines kept for an overlay preview
+const PREVIEW_MAX_LINES: usize = 200;
+
+/// Number of previews kept in the cache
+const PREVIEW_CACHE_SIZE: usize = 64;
+
+/// Files larger than this are parsed and rendered in the background
+const BACKGROUND_RENDER_BYTES: usize = 512 * 1024;
+
+/// Source lines rendered up front while a large file renders in the background
+const FIRST_SCREEN_LINES: usize = 300;
+
+/// Rendered lines highlighted beyond each edge of the view, so code blocks are
+/// ready before they scroll in
+const HIGHLIGHT_MARGIN: usize = 50;
+
+/// A parsed document and its rendered lines
+type RenderedDocument = (Document, Vec<ratatui::text::Line<'static>>);
 
 /// Result type for async URL fetch: Ok((content, url)) or Err(error_message)
 pub type FetchResult = Result<(String, String), String>;
@@ -21,6 +65,8 @@ pub enum InputMode {
     SplitCommand,
     UrlInput,
     BookmarkName,
+    GlobalSearch,
+    Command,
 }
 
 /// Split direction for panes
@@ -38,31 +84,91 @@ pub enum FocusedPanel {
     #[default]
     Content,
     Outline,
+    FileTree,
+}
+
+/// What the search results overlay searches
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SearchScope {
+    /// All open document buffers
+    #[default]
+    Buffers,
+    /// All markdown files under the current directory
+    Project,
+}
+
+/// What the mouse is currently dragging
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DragTarget {
+    /// Right border of the outline panel
+    OutlineBorder,
+    /// Border between split panes
+    SplitBorder,
+    /// Scrollbar thumb of a pane
+    Scrollbar(usize),
+}
+
+/// Search state saved when entering search mode (restored on cancel)
+#[derive(Clone, Debug, Default)]
+pub struct SearchSnapshot {
+    pub scroll: usize,
+    pub query: String,
+    pub matches: Vec<SearchMatch>,
+    pub current_match: usize,
+}
+
+/// A large file being parsed and rendered on a background thread
+pub struct RenderJob {
+    pub path: PathBuf,
+    /// Whether this replaces a shown document after a reload (anchor the
+    /// view and highlight changes when it finishes)
+    pub reload: bool,
+    pub receiver: Receiver<RenderedDocument>,
+}
+
+/// Where to position the first document once it is loaded (from the CLI)
+#[derive(Clone, Debug, Default)]
+pub struct StartupJump {
+    /// Source line (1-based)
+    pub line: Option<usize>,
+    pub heading: Option<String>,
+    pub search: Option<String>,
+}
+
+/// Rendered preview of a document shown in list overlays
+#[derive(Clone)]
+pub struct Preview {
+    /// File modification time when rendered (None for URLs)
+    modified: Option<SystemTime>,
+    pub lines: Vec<ratatui::text::Line<'static>>,
 }
 
-/// Search match location
+/// An entry in the file picker list
 #[derive(Clone, Debug)]
-pub struct SearchMatch {
-    pub line: usize,
-    pub start: usize,
-    pub end: usize,
+pub struct PickerEntry {
+    pub path: PathBuf,
+    /// Path relative to the picker directory, as shown in the list
+    pub display: String,
+    pub is_dir: bool,
+    /// Char indices in `display` matched by the filter
+    pub matched: Vec<usize>,
 }
 
 /// State for a single pane
 #[derive(Clone, Default)]
 pub struct PaneState {
-    /// Vertical scroll position (line index)
-    pub scroll: usize,
-    /// Horizontal scroll position (when wrap off)
-    pub horizontal_scroll: usize,
+    /// Scroll position and search matches
+    pub view: MarkdownViewState,
     /// Search query for this pane
     pub search_query: String,
     /// Whether search is regex
     pub search_is_regex: bool,
-    /// Search matches
-    pub search_matches: Vec<SearchMatch>,
-    /// Current match index
-    pub current_match: usize,
+    /// Whether search only matches whole words
+    pub search_whole_word: bool,
+    /// Error from the last search (e.g. invalid regex)
+    pub search_error: Option<String>,
+    /// State before the current search started
+    pub search_snapshot: Option<SearchSnapshot>,
     /// Textarea for search input
     pub search_textarea: TextArea<'static>,
 }
@@ -75,13 +181,16 @@ impl PaneState {
 
     /// Clone for split (same position, clear search)
     pub fn clone_for_split(&self) -> Self {
+        let mut view = MarkdownViewState::new();
+        view.scroll = self.view.scroll;
+        view.horizontal_scroll = self.view.horizontal_scroll;
         Self {
-            scroll: self.scroll,
-            horizontal_scroll: self.horizontal_scroll,
+            view,
             search_query: String::new(),
             search_is_regex: false,
-            search_matches: Vec::new(),
-            current_match: 0,
+            search_whole_word: false,
+            search_error: None,
+            search_snapshot: None,
             search_textarea: TextArea::default(),
         }
     }
@@ -90,14 +199,32 @@ impl PaneState {
 /// A document buffer (open file with state)
 #[derive(Clone)]
 pub struct DocumentBuffer {
-    pub document: Document,
+    /// The parsed document and its rendered lines. `None` while the buffer
+    /// is shown: the view owns them then, so each document is stored once.
+    pub content: Option<RenderedDocument>,
+    /// `content` was rendered with a theme or highlighting setting that has
+    /// since changed, and is re-rendered when the buffer is shown
+    pub stale: bool,
     pub file_path: PathBuf,
     pub url: Option<String>,
-    pub rendered_lines: Vec<ratatui::text::Line<'static>>,
     pub scroll: usize,
     pub horizontal_scroll: usize,
     pub outline_selected: usize,
     pub modified_time: Option<SystemTime>,
+    /// The file changed on disk while the buffer was in the background
+    pub changed_on_disk: bool,
+    /// The file was deleted on disk
+    pub deleted: bool,
+}
+
+impl DocumentBuffer {
+    /// Name shown in buffer lists and search results
+    pub fn display_name(&self) -> String {
+        self.file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.file_path.to_string_lossy().to_string())
+    }
 }
 
 /// Main application state
@@ -119,6 +246,8 @@ pub struct AppState {
     pub panes: Vec<PaneState>,
     pub active_pane: usize,
     pub split_direction: SplitDirection,
+    /// Size of the first pane in a split, in percent
+    pub split_ratio: u16,
 
     // UI state
     pub mode: InputMode,
@@ -134,33 +263,91 @@ pub struct AppState {
     pub show_line_numbers: bool,
     pub theme: Theme,
     pub syntax_highlighting: bool,
-    pub highlighter: SyntaxHighlighter,
+    /// Syntax highlighting theme chosen in place of the theme's own
+    pub syntax_theme: Option<String>,
+    /// Loaded on a background thread; code blocks are rendered plain until
+    /// it arrives and highlighted as they come into view
+    pub highlighter: Option<SyntaxHighlighter>,
+    pub highlighter_receiver: Option<Receiver<(SyntaxHighlighter, Option<String>)>>,
 
     // Outline state
     pub outline_selected: usize,
     pub outline_width: u16,
 
+    // File tree state
+    pub show_file_tree: bool,
+    pub file_tree: FileTree,
+    pub file_tree_selected: usize,
+    pub file_tree_scroll: usize,
+    pub file_tree_area: Rect,
+
     // Settings overlay state
     pub settings_selected: usize,
 
+    // Code theme picker state (opened from Settings). The first choice is
+    // the theme's own syntax theme.
+    pub show_syntax_theme_picker: bool,
+    pub syntax_theme_choices: Vec<String>,
+    pub syntax_theme_selected: usize,
+
     // File picker state
     pub show_file_picker: bool,
+    pub file_picker_dir: PathBuf,
+    pub file_picker_dirs: Vec<PathBuf>,
     pub file_picker_files: Vec<PathBuf>,
+    pub file_picker_entries: Vec<PickerEntry>,
+    pub file_picker_textarea: TextArea<'static>,
     pub file_picker_selected: usize,
 
+    /// Alternate config file (from `--config`), used when saving settings
+    pub config_path: Option<PathBuf>,
+    /// Startup position waiting for a URL to finish loading
+    pub startup_jump: Option<StartupJump>,
+
+    // Follow mode (streaming a growing file or stdin)
+    pub follow_receiver: Option<Receiver<FollowMessage>>,
+    /// Path of the followed document (a file, or `<stdin>`)
+    pub follow_path: Option<PathBuf>,
+    pub follow_content: String,
+    /// Background render of `follow_content`; text arriving meanwhile is
+    /// rendered together once it finishes
+    pub follow_render: Option<Receiver<RenderedDocument>>,
+    /// `follow_content` changed since the last render started
+    pub follow_dirty: bool,
+
+    /// Wakes the main loop when background work finishes
+    pub waker: Waker,
+
     // Live reload
     pub auto_reload: bool,
     pub file_modified_time: Option<SystemTime>,
+    /// Whether the current file was deleted on disk
+    pub file_deleted: bool,
+    /// Watches the files of all open buffers
+    pub file_watcher: Option<FileWatcher>,
+    /// Scroll to the first changed line when a file reloads
+    pub jump_to_change: bool,
+    /// Lines changed by the last reload, briefly highlighted
+    pub change_highlight: Option<ChangeHighlight>,
 
     // URL support
     pub github_fetcher: GitHubFetcher,
     pub current_url: Option<String>,
     pub is_loading: bool,
     pub fetch_receiver: Option<Receiver<FetchResult>>,
+    /// Background fetches for URL buffers, keyed by URL
+    pub buffer_fetches: Vec<(String, Receiver<FetchResult>)>,
+    /// Large files being rendered in the background, keyed by path
+    pub render_jobs: Vec<RenderJob>,
 
     // History & Bookmarks
     pub history: History,
     pub bookmarks: Bookmarks,
+    pub search_history: SearchHistory,
+    /// Position while recalling search history (None = editing a new query)
+    pub search_history_index: Option<usize>,
+    /// Query typed before recalling history
+    pub search_history_draft: String,
 
     // History overlay state
     pub show_history: bool,
@@ -170,23 +357,50 @@ pub struct AppState {
     pub show_bookmarks: bool,
     pub bookmarks_selected: usize,
 
+    // Overlay preview state (keyed by file path or URL)
+    pub preview_cache: HashMap<String, Preview>,
+    pub preview_location: Option<String>,
+
     // URL input state
     pub show_url_input: bool,
     pub url_textarea: TextArea<'static>,
 
+    // Command line state (`:`)
+    pub command_textarea: TextArea<'static>,
+
     // Bookmark name input state
     pub show_bookmark_name_input: bool,
     pub bookmark_name_textarea: TextArea<'static>,
 
+    // Global search overlay state
+    pub show_global_search: bool,
+    pub global_search_textarea: TextArea<'static>,
+    pub global_search_options: SearchOptions,
+    pub global_search_results: Vec<SearchHit>,
+    pub global_search_selected: usize,
+    pub global_search_error: Option<String>,
+    pub global_search_scope: SearchScope,
+
+    // Project search (background thread)
+    pub project_search_receiver: Option<Receiver<ProjectSearchMessage>>,
+    pub project_search_cancel: Option<Arc<AtomicBool>>,
+    /// Query and options of the last project search that was started
+    pub project_search_last: Option<(String, SearchOptions)>,
+    pub project_search_running: bool,
+
     // Layout tracking (for mouse)
     pub content_areas: Vec<Rect>,
+    /// Text area of each pane (inside borders, excluding line numbers)
+    pub pane_text_areas: Vec<Rect>,
     pub outline_area: Rect,
+    pub drag_target: Option<DragTarget>,
 }
 
 impl AppState {
     /// Create new app state from config
     pub fn new(config: &Config) -> Self {
-        Self {
+        let waker = Waker::default();
+        let mut state = Self {
             document: None,
             file_path: None,
             rendered_lines: Vec::new(),
@@ -199,6 +413,7 @@ impl AppState {
             panes: vec![PaneState::new()],
             active_pane: 0,
             split_direction: SplitDirection::None,
+            split_ratio: 50,
 
             mode: InputMode::Normal,
             focused_panel: FocusedPanel::Content,
@@ -212,49 +427,101 @@ impl AppState {
             show_line_numbers: config.show_line_numbers,
             theme: config.get_theme(),
             syntax_highlighting: config.syntax_highlighting,
-            highlighter: SyntaxHighlighter::default(),
+            syntax_theme: config.syntax_theme.clone(),
+            highlighter: None,
+            highlighter_receiver: None,
 
             outline_selected: 0,
             outline_width: config.outline_width,
             settings_selected: 0,
 
+            show_syntax_theme_picker: false,
+            syntax_theme_choices: Vec::new(),
+            syntax_theme_selected: 0,
+
             show_file_picker: false,
+            file_picker_dir: PathBuf::from("."),
+            file_picker_dirs: Vec::new(),
             file_picker_files: Vec::new(),
+            file_picker_entries: Vec::new(),
+            file_picker_textarea: TextArea::default(),
             file_picker_selected: 0,
 
+            config_path: None,
+            startup_jump: None,
+            follow_receiver: None,
+            follow_path: None,
+            follow_content: String::new(),
+            follow_render: None,
+            follow_dirty: false,
+            file_watcher: FileWatcher::new(&waker),
+            waker,
             auto_reload: config.auto_reload,
             file_modified_time: None,
+            file_deleted: false,
+            jump_to_change: config.jump_to_change,
+            change_highlight: None,
 
             github_fetcher: GitHubFetcher::new(),
             current_url: None,
             is_loading: false,
             fetch_receiver: None,
+            buffer_fetches: Vec::new(),
+            render_jobs: Vec::new(),
 
             history: History::load(),
             bookmarks: Bookmarks::load(),
+            search_history: SearchHistory::load(),
+            search_history_index: None,
+            search_history_draft: String::new(),
 
             show_history: false,
             history_selected: 0,
 
             show_bookmarks: false,
             bookmarks_selected: 0,
+            preview_cache: HashMap::new(),
+            preview_location: None,
 
             show_url_input: false,
             url_textarea: TextArea::default(),
+            command_textarea: TextArea::default(),
 
             show_bookmark_name_input: false,
             bookmark_name_textarea: TextArea::default(),
 
+            show_global_search: false,
+            global_search_textarea: TextArea::default(),
+            global_search_options: SearchOptions::default(),
+            global_search_results: Vec::new(),
+            global_search_selected: 0,
+            global_search_error: None,
+            global_search_scope: SearchScope::Buffers,
+
+            project_search_receiver: None,
+            project_search_cancel: None,
+            project_search_last: None,
+            project_search_running: false,
+
             content_areas: Vec::new(),
+            pane_text_areas: Vec::new(),
             outline_area: Rect::default(),
+            show_file_tree: false,
+            file_tree: FileTree::default(),
+            file_tree_selected: 0,
+            file_tree_scroll: 0,
+            file_tree_area: Rect::default(),
+            drag_target: None,
+        };
+
+        if state.syntax_highlighting {
+            state.load_highlighter();
         }
+        state
     }
 
     /// Load a markdown file
     pub fn load_file(&mut self, path: &std::path::Path) -> anyhow::Result<()> {
-        // Save current document to buffer before loading new one
-        self.save_to_buffer();
-
         // Check if this file is already in a buffer
         let abs_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
         if let Some(idx) = self.buffers.iter().position(|b| {
@@ -269,24 +536,27 @@ impl AppState {
         }
 
         let content = std::fs::read_to_string(path)?;
-        let mut document = Document::parse(&content);
-
-        // Pre-render lines with optional syntax highlighting
-        let highlighter = if self.syntax_highlighting {
-            Some(&self.highlighter)
+        let (document, rendered_lines) = if content.len() > BACKGROUND_RENDER_BYTES {
+            // Show the first screen now, the rest once rendered
+            let first_screen = first_lines(&content, FIRST_SCREEN_LINES);
+            let rendered = self.render_content(first_screen);
+            self.start_render_job(path.to_path_buf(), content, false);
+            rendered
         } else {
-            None
+            self.render_content(&content)
         };
-        self.rendered_lines = document.render_with_highlighting(&self.theme, highlighter);
 
+        // Put the current document back in its buffer before showing the new one
+        self.park_current_buffer();
         self.document = Some(document);
+        self.rendered_lines = rendered_lines;
         self.file_path = Some(path.to_path_buf());
 
         // Reset pane state
         for pane in &mut self.panes {
-            pane.scroll = 0;
-            pane.horizontal_scroll = 0;
-            pane.search_matches.clear();
+            pane.view.scroll = 0;
+            pane.view.horizontal_scroll = 0;
+            pane.view.search_matches.clear();
         }
 
         self.outline_selected = 0;
@@ -294,6 +564,10 @@ impl AppState {
 
         // Store file modification time for auto-reload
         self.file_modified_time = std::fs::metadata(path).ok().and_then(|m| m.modified().ok());
+        self.file_deleted = false;
+        if let Some(watcher) = &mut self.file_watcher {
+            watcher.watch(path);
+        }
 
         // Add to buffer list
         self.save_to_buffer();
@@ -312,51 +586,171 @@ impl AppState {
         Ok(())
     }
 
-    /// Check if the current file has changed and reload if needed
-    pub fn check_file_changed(&mut self) -> bool {
-        if !self.auto_reload {
-            return false;
+    /// Load a document read from standard input (shown as `<stdin>`, never auto-reloaded)
+    pub fn load_stdin(&mut self, content: &str) {
+        self.park_current_buffer();
+
+        let (document, rendered_lines) = self.render_content(content);
+        self.document = Some(document);
+        self.rendered_lines = rendered_lines;
+        self.file_path = Some(PathBuf::from(STDIN_PATH));
+        self.current_url = None;
+        self.file_modified_time = None;
+        self.file_deleted = false;
+
+        for pane in &mut self.panes {
+            pane.view.scroll = 0;
+            pane.view.horizontal_scroll = 0;
+            pane.view.search_matches.clear();
         }
+        self.outline_selected = 0;
 
-        let Some(path) = &self.file_path else {
+        self.save_to_buffer();
+        self.history.add(STDIN_PATH, false, STDIN_PATH);
+    }
+
+    /// Apply changes reported by the file watcher: reload the current file,
+    /// and mark background buffers whose files changed or were deleted.
+    /// Returns whether anything changed.
+    pub fn check_file_changed(&mut self) -> bool {
+        let Some(watcher) = &mut self.file_watcher else {
             return false;
         };
+        let changes = watcher.poll();
+        if changes.is_empty() {
+            return false;
+        }
+
+        let current = self
+            .file_path
+            .as_ref()
+            .filter(|_| self.current_url.is_none() && !self.is_viewing_stdin())
+            .map(|p| watch::watch_key(p));
+        let follow_key = self.follow_path.as_ref().map(|p| watch::watch_key(p));
+
+        for change in changes {
+            let (path, deleted) = match change {
+                FileChange::Modified(path) => (path, false),
+                FileChange::Deleted(path) => (path, true),
+            };
+
+            if current.as_ref() == Some(&path) {
+                if self.is_following_current() {
+                    continue;
+                }
+                if deleted {
+                    self.file_deleted = true;
+                    self.status_message = Some("File deleted on disk".to_string());
+                } else if self.auto_reload {
+                    self.reload_current_file();
+                } else {
+                    self.status_message = Some("File changed on disk".to_string());
+                }
+                continue;
+            }
+
+            for buffer in &mut self.buffers {
+                if buffer.url.is_some() || follow_key.as_ref() == Some(&path) {
+                    continue;
+                }
+                if watch::watch_key(&buffer.file_path) == path {
+                    buffer.deleted = deleted;
+                    buffer.changed_on_disk = !deleted;
+                    self.status_message = Some(format!(
+                        "{} {} on disk",
+                        buffer.display_name(),
+                        if deleted { "deleted" } else { "changed" }
+                    ));
+                }
+            }
+        }
+
+        true
+    }
 
-        let Ok(metadata) = std::fs::metadata(path) else {
+    /// Re-read the current file from disk, keeping the view anchored to the
+    /// same content and highlighting what changed
+    fn reload_current_file(&mut self) -> bool {
+        let Some(path) = self.file_path.clone() else {
             return false;
         };
-
-        let Ok(current_modified) = metadata.modified() else {
+        let modified = std::fs::metadata(&path)
+            .ok()
+            .and_then(|m| m.modified().ok());
+        if modified.is_some() && modified == self.file_modified_time && !self.file_deleted {
+            // Touched (or atomically replaced) without new content
+            return false;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
             return false;
         };
 
-        // Check if modification time has changed
-        if let Some(stored_time) = self.file_modified_time {
-            if current_modified > stored_time {
-                // File has been modified, reload it
-                let path_clone = path.clone();
+        self.file_modified_time = modified;
+        self.file_deleted = false;
 
-                // Read and re-parse the file
-                if let Ok(content) = std::fs::read_to_string(&path_clone) {
-                    let mut document = Document::parse(&content);
+        // Only the first screen is shown while the initial render is still
+        // running, so there is nothing to compare the new content with
+        let reload = self
+            .render_jobs
+            .iter()
+            .find(|job| job.path == path)
+            .is_none_or(|job| job.reload);
+        self.render_jobs.retain(|job| job.path != path);
 
-                    // Re-render with current settings
-                    let highlighter = if self.syntax_highlighting {
-                        Some(&self.highlighter)
-                    } else {
-                        None
-                    };
-                    self.rendered_lines =
-                        document.render_with_highlighting(&self.theme, highlighter);
-                    self.document = Some(document);
-                    self.file_modified_time = Some(current_modified);
-                    self.status_message = Some("File reloaded".to_string());
-                    return true;
-                }
+        if content.len() > BACKGROUND_RENDER_BYTES {
+            self.start_render_job(path, content, reload);
+            self.status_message = Some("Reloading...".to_string());
+        } else {
+            let rendered = self.render_content(&content);
+            if reload {
+                self.finish_reload(path, rendered);
+            } else {
+                self.finish_render(rendered);
+            }
+        }
+        true
+    }
+
+    /// Swap in a reloaded current document, keeping the view anchored to the
+    /// same content and highlighting what changed
+    fn finish_reload(&mut self, path: PathBuf, (document, rendered_lines): RenderedDocument) {
+        let diff = LineDiff::new(&self.rendered_lines, &rendered_lines);
+
+        // Keep the same content at the top of every pane
+        if let Some(old) = &self.document {
+            for pane in &mut self.panes {
+                pane.view.scroll = reload::anchor_scroll(old, &document, &diff, pane.view.scroll);
             }
         }
 
-        false
+        self.document = Some(document);
+        self.rendered_lines = rendered_lines;
+        self.refresh_search_matches();
+
+        if self.jump_to_change
+            && let Some(&first) = diff.changed.first()
+        {
+            let height = self
+                .pane_text_areas
+                .get(self.active_pane)
+                .map_or(1, |area| area.height.max(1) as usize);
+            let pane = self.current_pane_mut();
+            if first < pane.view.scroll || first >= pane.view.scroll + height {
+                pane.view.scroll = first.saturating_sub(height / 3);
+            }
+        }
+
+        self.status_message = Some(match diff.changed.len() {
+            0 => "File reloaded".to_string(),
+            1 => "File reloaded (1 line changed)".to_string(),
+            n => format!("File reloaded ({} lines changed)", n),
+        });
+        self.change_highlight = Some(ChangeHighlight {
+            path,
+            lines: diff.changed,
+            since: std::time::Instant::now(),
+        });
+        self.save_to_buffer();
     }
 
     /// Toggle auto-reload
@@ -367,23 +761,61 @@ impl AppState {
         } else {
             "Auto-reload disabled".to_string()
         });
+
+        // Catch up with changes made while auto-reload was off
+        if self.auto_reload
+            && self.current_url.is_none()
+            && !self.is_viewing_stdin()
+            && !self.is_following_current()
+        {
+            self.reload_current_file();
+        }
+    }
+
+    /// Toggle scrolling to the first change on reload
+    pub fn toggle_jump_to_change(&mut self) {
+        self.jump_to_change = !self.jump_to_change;
+        self.status_message = Some(if self.jump_to_change {
+            "Jump to changes enabled".to_string()
+        } else {
+            "Jump to changes disabled".to_string()
+        });
+    }
+
+    /// When the main loop has to wake up without an event: to release
+    /// debounced file changes, or to redraw once a change highlight expires
+    pub fn next_deadline(&self) -> Option<std::time::Instant> {
+        let debounce = self
+            .file_watcher
+            .as_ref()
+            .and_then(FileWatcher::next_deadline);
+        let highlight = self
+            .change_highlight
+            .as_ref()
+            .map(|h| h.since + reload::CHANGE_HIGHLIGHT)
+            .filter(|&end| end > std::time::Instant::now());
+        debounce.into_iter().chain(highlight).min()
     }
 
-    /// Re-render document (e.g., after theme change)
+    /// Re-render document (e.g., after theme change). Background buffers are
+    /// re-rendered when they are next shown.
     pub fn rerender(&mut self) {
-        if let Some(doc) = &mut self.document {
-            let highlighter = if self.syntax_highlighting {
-                Some(&self.highlighter)
-            } else {
-                None
-            };
-            self.rendered_lines = doc.render_with_highlighting(&self.theme, highlighter);
+        self.preview_cache.clear();
+        if let Some(mut doc) = self.document.take() {
+            self.rendered_lines = self.render_document(&mut doc);
+            self.document = Some(doc);
+        }
+        for buffer in &mut self.buffers {
+            buffer.stale = buffer.content.is_some();
         }
     }
 
     /// Toggle syntax highlighting
     pub fn toggle_syntax_highlighting(&mut self) {
         self.syntax_highlighting = !self.syntax_highlighting;
+        if self.syntax_highlighting {
+            self.load_highlighter();
+        }
         self.rerender();
     }
 
@@ -408,13 +840,13 @@ impl AppState {
     pub fn scroll_down(&mut self) {
         let max_scroll = self.line_count().saturating_sub(1);
         let pane = self.current_pane_mut();
-        pane.scroll = pane.scroll.saturating_add(1).min(max_scroll);
+        pane.view.scroll = pane.view.scroll.saturating_add(1).min(max_scroll);
     }
 
     /// Scroll up one line
     pub fn scroll_up(&mut self) {
         let pane = self.current_pane_mut();
-        pane.scroll = pane.scroll.saturating_sub(1);
+        pane.view.scroll = pane.view.scroll.saturating_sub(1);
     }
 
     /// Scroll down half page
@@ -422,32 +854,32 @@ impl AppState {
         let half_page = page_size / 2;
         let max_scroll = self.line_count().saturating_sub(1);
         let pane = self.current_pane_mut();
-        pane.scroll = pane.scroll.saturating_add(half_page).min(max_scroll);
+        pane.view.scroll = pane.view.scroll.saturating_add(half_page).min(max_scroll);
     }
 
     /// Scroll up half page
     pub fn scroll_page_up(&mut self, page_size: usize) {
         let half_page = page_size / 2;
         let pane = self.current_pane_mut();
-        pane.scroll = pane.scroll.saturating_sub(half_page);
+        pane.view.scroll = pane.view.scroll.saturating_sub(half_page);
     }
 
     /// Go to top of document
     pub fn go_to_top(&mut self) {
-        self.current_pane_mut().scroll = 0;
+        self.current_pane_mut().view.scroll = 0;
     }
 
     /// Go to bottom of document
     pub fn go_to_bottom(&mut self) {
         let max_scroll = self.line_count().saturating_sub(1);
-        self.current_pane_mut().scroll = max_scroll;
+        self.current_pane_mut().view.scroll = max_scroll;
     }
 
     /// Scroll left
     pub fn scroll_left(&mut self) {
         if !self.line_wrap {
             let pane = self.current_pane_mut();
-            pane.horizontal_scroll = pane.horizontal_scroll.saturating_sub(4);
+            pane.view.horizontal_scroll = pane.view.horizontal_scroll.saturating_sub(4);
         }
     }
 
@@ -455,14 +887,72 @@ impl AppState {
     pub fn scroll_right(&mut self) {
         if !self.line_wrap {
             let pane = self.current_pane_mut();
-            pane.horizontal_scroll = pane.horizontal_scroll.saturating_add(4);
+            pane.view.horizontal_scroll = pane.view.horizontal_scroll.saturating_add(4);
         }
     }
 
     /// Go to specific line
     pub fn go_to_line(&mut self, line: usize) {
         let max_scroll = self.line_count().saturating_sub(1);
-        self.current_pane_mut().scroll = line.min(max_scroll);
+        self.current_pane_mut().view.scroll = line.min(max_scroll);
+    }
+
+    /// Go to the rendered line for a source line (1-based)
+    pub fn go_to_source_line(&mut self, line: usize) {
+        if let Some(doc) = &self.document {
+            let rendered = doc.rendered_line_for_source(line.saturating_sub(1));
+            self.go_to_line(rendered);
+        }
+    }
+
+    /// Go to the first heading matching `name` (exact, then substring; case-insensitive)
+    pub fn go_to_heading(&mut self, name: &str) -> bool {
+        let Some(doc) = &self.document else {
+            return false;
+        };
+
+        match outline::find_heading(&doc.headings, name) {
+            Some(index) => {
+                let line = doc.headings[index].rendered_line;
+                self.outline_selected = index;
+                self.go_to_line(line);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Run a search as if typed in the search prompt
+    pub fn search_for(&mut self, query: &str) {
+        self.start_search();
+        self.current_pane_mut().search_textarea = TextArea::new(vec![query.to_string()]);
+        self.apply_search();
+    }
+
+    /// Apply a startup position now, or once the pending URL fetch finishes
+    pub fn apply_startup_jump(&mut self, jump: StartupJump) {
+        if self.is_rendering() {
+            self.startup_jump = Some(jump);
+            return;
+        }
+        if self.document.is_none() {
+            if self.is_loading {
+                self.startup_jump = Some(jump);
+            }
+            return;
+        }
+
+        if let Some(line) = jump.line {
+            self.go_to_source_line(line);
+        }
+        if let Some(heading) = &jump.heading
+            && !self.go_to_heading(heading)
+        {
+            self.status_message = Some(format!("Heading not found: {}", heading));
+        }
+        if let Some(query) = &jump.search {
+            self.search_for(query);
+        }
     }
 
     // === Outline navigation ===
@@ -501,180 +991,685 @@ impl AppState {
     pub fn start_search(&mut self) {
         self.mode = InputMode::Search;
         let pane = self.current_pane_mut();
+        pane.search_snapshot = Some(SearchSnapshot {
+            scroll: pane.view.scroll,
+            query: pane.search_query.clone(),
+            matches: pane.view.search_matches.clone(),
+            current_match: pane.view.current_match,
+        });
         pane.search_textarea = TextArea::default();
+        pane.search_error = None;
+        self.search_history_index = None;
+    }
+
+    /// Search options of the current pane
+    pub fn search_options(&self) -> SearchOptions {
+        let pane = self.current_pane();
+        SearchOptions {
+            regex: pane.search_is_regex,
+            whole_word: pane.search_whole_word,
+        }
+    }
+
+    /// Re-run the search as the query is typed (incremental search)
+    pub fn update_search(&mut self) {
+        let query = self.current_pane().search_textarea.lines().join("");
+        let origin = self
+            .current_pane()
+            .search_snapshot
+            .as_ref()
+            .map_or(self.current_pane().view.scroll, |s| s.scroll);
+
+        let options = self.search_options();
+        let pane = &mut self.panes[self.active_pane];
+        pane.search_query = query;
+        // Start from the first match at or below where the search began
+        pane.view.scroll = origin;
+        match pane
+            .view
+            .search(&self.rendered_lines, &pane.search_query, options)
+        {
+            Ok(_) => pane.search_error = None,
+            Err(e) => {
+                pane.view.clear_search();
+                pane.search_error = Some(e);
+            }
+        }
     }
 
     /// Apply search and find matches
     pub fn apply_search(&mut self) {
-        let query = self.current_pane().search_textarea.lines().join("");
-        if query.is_empty() {
-            self.mode = InputMode::Normal;
+        self.update_search();
+        self.mode = InputMode::Normal;
+
+        let pane = self.current_pane_mut();
+        pane.search_snapshot = None;
+
+        if pane.search_query.is_empty() {
             return;
         }
 
-        let is_regex = self.current_pane().search_is_regex;
-        let mut matches = Vec::new();
+        if self.current_pane().search_error.is_none() {
+            let pane = &self.panes[self.active_pane];
+            self.search_history
+                .add(&pane.search_query, pane.search_is_regex);
+            let _ = self.search_history.save();
+        }
 
-        // Build regex or literal pattern
-        let pattern = if is_regex {
-            match regex::Regex::new(&query) {
-                Ok(r) => Some(r),
-                Err(_) => {
-                    self.status_message = Some("Invalid regex".to_string());
-                    self.mode = InputMode::Normal;
-                    return;
-                }
-            }
-        } else {
-            regex::Regex::new(&regex::escape(&query)).ok()
-        };
+        self.status_message = Some(match &self.current_pane().search_error {
+            Some(e) => format!("Invalid regex: {}", e),
+            None => format!(
+                "{} matches found",
+                self.current_pane().view.search_matches.len()
+            ),
+        });
+    }
 
-        if let Some(re) = pattern {
-            for (line_idx, line) in self.rendered_lines.iter().enumerate() {
-                let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
-                for m in re.find_iter(&text) {
-                    matches.push(SearchMatch {
-                        line: line_idx,
-                        start: m.start(),
-                        end: m.end(),
-                    });
-                }
-            }
+    /// Recall an older search from history
+    pub fn search_history_older(&mut self) {
+        let next = self.search_history_index.map_or(0, |i| i + 1);
+        if next >= self.search_history.entries().len() {
+            return;
         }
 
-        let match_count = matches.len();
-        let pane = self.current_pane_mut();
-        pane.search_query = query;
-        pane.search_matches = matches;
-        pane.current_match = 0;
-
-        self.mode = InputMode::Normal;
-        self.status_message = Some(format!("{} matches found", match_count));
+        if self.search_history_index.is_none() {
+            self.search_history_draft = self.current_pane().search_textarea.lines().join("");
+        }
+        self.recall_search_history(Some(next));
+    }
 
-        // Jump to first match
-        if match_count > 0 {
-            self.jump_to_current_match();
+    /// Recall a newer search from history, returning to the draft at the end
+    pub fn search_history_newer(&mut self) {
+        match self.search_history_index {
+            None => {}
+            Some(0) => self.recall_search_history(None),
+            Some(i) => self.recall_search_history(Some(i - 1)),
         }
     }
 
-    /// Cancel search
+    /// Put a history entry (or the draft) into the search prompt
+    fn recall_search_history(&mut self, index: Option<usize>) {
+        self.search_history_index = index;
+
+        let (query, is_regex) = match index.and_then(|i| self.search_history.entries().get(i)) {
+            Some(entry) => (entry.query.clone(), entry.is_regex),
+            None => (
+                self.search_history_draft.clone(),
+                self.current_pane().search_is_regex,
+            ),
+        };
+
+        let pane = self.current_pane_mut();
+        pane.search_is_regex = is_regex;
+        pane.search_textarea = TextArea::new(vec![query]);
+        pane.search_textarea.move_cursor(CursorMove::End);
+        self.update_search();
+    }
+
+    /// Cancel search, restoring the previous search and position
     pub fn cancel_search(&mut self) {
         self.mode = InputMode::Normal;
+        let pane = self.current_pane_mut();
+        if let Some(snapshot) = pane.search_snapshot.take() {
+            pane.view.scroll = snapshot.scroll;
+            pane.search_query = snapshot.query;
+            pane.view.search_matches = snapshot.matches;
+            pane.view.current_match = snapshot.current_match;
+        }
+        pane.search_error = None;
     }
 
     /// Clear search
     pub fn clear_search(&mut self) {
         let pane = self.current_pane_mut();
         pane.search_query.clear();
-        pane.search_matches.clear();
-        pane.current_match = 0;
+        pane.view.clear_search();
+        pane.search_error = None;
         self.status_message = None;
     }
 
     /// Next search match
     pub fn next_match(&mut self) {
-        let pane = self.current_pane_mut();
-        if !pane.search_matches.is_empty() {
-            pane.current_match = (pane.current_match + 1) % pane.search_matches.len();
-        }
-        self.jump_to_current_match();
+        self.current_pane_mut().view.next_match();
     }
 
     /// Previous search match
     pub fn prev_match(&mut self) {
-        let pane = self.current_pane_mut();
-        if !pane.search_matches.is_empty() {
-            pane.current_match = if pane.current_match == 0 {
-                pane.search_matches.len() - 1
-            } else {
-                pane.current_match - 1
-            };
-        }
-        self.jump_to_current_match();
-    }
-
-    /// Jump viewport to current match
-    fn jump_to_current_match(&mut self) {
-        let pane = &self.panes[self.active_pane];
-        if let Some(m) = pane.search_matches.get(pane.current_match) {
-            let line = m.line;
-            self.go_to_line(line);
-        }
+        self.current_pane_mut().view.prev_match();
     }
 
     /// Toggle regex search
     pub fn toggle_regex(&mut self) {
         self.current_pane_mut().search_is_regex = !self.current_pane().search_is_regex;
+        self.update_search();
     }
 
-    // === Split view ===
+    /// Toggle whole-word search
+    pub fn toggle_whole_word(&mut self) {
+        self.current_pane_mut().search_whole_word = !self.current_pane().search_whole_word;
+        self.update_search();
+    }
 
-    /// Split vertically
-    pub fn split_vertical(&mut self) {
-        if self.split_direction == SplitDirection::None {
-            let new_pane = self.current_pane().clone_for_split();
-            self.panes.push(new_pane);
-            self.split_direction = SplitDirection::Vertical;
-            self.active_pane = 1;
+    // === Global search ===
+
+    /// Open the search-all-buffers overlay
+    pub fn start_global_search(&mut self) {
+        if self.document.is_none() {
+            self.status_message = Some("No buffers open".to_string());
+            return;
         }
+
+        // Make sure the shown document has a buffer to search
+        self.save_to_buffer();
+        self.open_results_search(SearchScope::Buffers);
     }
 
-    /// Split horizontally
-    pub fn split_horizontal(&mut self) {
-        if self.split_direction == SplitDirection::None {
-            let new_pane = self.current_pane().clone_for_split();
-            self.panes.push(new_pane);
-            self.split_direction = SplitDirection::Horizontal;
-            self.active_pane = 1;
-        }
+    /// Open the project-wide search overlay
+    pub fn start_project_search(&mut self) {
+        self.save_to_buffer();
+        self.open_results_search(SearchScope::Project);
+    }
+
+    /// Show the search results overlay for a scope
+    fn open_results_search(&mut self, scope: SearchScope) {
+        self.cancel_project_search();
+        self.mode = InputMode::GlobalSearch;
+        self.show_global_search = true;
+        self.global_search_scope = scope;
+        self.global_search_textarea = TextArea::default();
+        self.global_search_results.clear();
+        self.global_search_selected = 0;
+        self.global_search_error = None;
+        self.project_search_last = None;
+    }
+
+    /// Switch the results overlay between open buffers and the project
+    pub fn toggle_search_scope(&mut self) {
+        self.cancel_project_search();
+        self.global_search_scope = match self.global_search_scope {
+            SearchScope::Buffers => SearchScope::Project,
+            SearchScope::Project => SearchScope::Buffers,
+        };
+        self.global_search_results.clear();
+        self.global_search_selected = 0;
+        self.global_search_error = None;
+        self.project_search_last = None;
+        self.update_global_search();
     }
 
-    /// Close current pane
-    pub fn close_pane(&mut self) {
-        if self.panes.len() > 1 {
-            self.panes.remove(self.active_pane);
-            self.split_direction = SplitDirection::None;
-            self.active_pane = 0;
+    /// Re-run the global search for the current query.
+    /// Project searches only run on Enter, since they read from disk.
+    pub fn update_global_search(&mut self) {
+        if self.global_search_scope == SearchScope::Project {
+            return;
         }
-    }
 
-    /// Cycle to next pane
-    pub fn cycle_pane(&mut self) {
-        if self.panes.len() > 1 {
-            self.active_pane = (self.active_pane + 1) % self.panes.len();
+        let query = self.global_search_textarea.lines().join("");
+        self.global_search_results.clear();
+        self.global_search_selected = 0;
+        self.global_search_error = None;
+
+        if query.is_empty() {
+            return;
         }
-    }
 
-    // === Display toggles ===
+        let pattern = match search::build_pattern(&query, self.global_search_options) {
+            Ok(re) => re,
+            Err(e) => {
+                self.global_search_error = Some(e);
+                return;
+            }
+        };
 
-    /// Toggle outline panel
-    pub fn toggle_outline(&mut self) {
-        self.show_outline = !self.show_outline;
-        if !self.show_outline && self.focused_panel == FocusedPanel::Outline {
-            self.focused_panel = FocusedPanel::Content;
+        for (idx, buffer) in self.buffers.iter().enumerate() {
+            let Some((document, rendered_lines)) = self.buffer_content(buffer) else {
+                continue;
+            };
+            self.global_search_results.extend(search::search_document(
+                rendered_lines,
+                &document.headings,
+                &pattern,
+                HitSource::Buffer(idx),
+            ));
         }
     }
 
-    /// Toggle line wrap
-    pub fn toggle_line_wrap(&mut self) {
-        self.line_wrap = !self.line_wrap;
+    /// Toggle regex for the global search
+    pub fn toggle_global_search_regex(&mut self) {
+        self.global_search_options.regex = !self.global_search_options.regex;
+        self.update_global_search();
     }
 
-    /// Toggle line numbers
-    pub fn toggle_line_numbers(&mut self) {
-        self.show_line_numbers = !self.show_line_numbers;
+    /// Toggle whole-word for the global search
+    pub fn toggle_global_search_whole_word(&mut self) {
+        self.global_search_options.whole_word = !self.global_search_options.whole_word;
+        self.update_global_search();
     }
 
-    /// Cycle focus between panels
-    pub fn cycle_focus(&mut self) {
-        if self.show_outline {
-            self.focused_panel = match self.focused_panel {
-                FocusedPanel::Content => FocusedPanel::Outline,
-                FocusedPanel::Outline => FocusedPanel::Content,
+    /// Move global search selection up
+    pub fn global_search_up(&mut self) {
+        if self.global_search_selected > 0 {
+            self.global_search_selected -= 1;
+        }
+    }
+
+    /// Move global search selection down
+    pub fn global_search_down(&mut self) {
+        let max = self.global_search_results.len().saturating_sub(1);
+        if self.global_search_selected < max {
+            self.global_search_selected += 1;
+        }
+    }
+
+    /// Close the global search overlay
+    pub fn close_global_search(&mut self) {
+        self.cancel_project_search();
+        self.mode = InputMode::Normal;
+        self.show_global_search = false;
+    }
+
+    /// Handle Enter in the results overlay: start a project search for a
+    /// new query, otherwise open the selected result
+    pub fn submit_global_search(&mut self) {
+        let query = self.global_search_textarea.lines().join("");
+        let current = Some((query, self.global_search_options));
+        if self.global_search_scope == SearchScope::Project && self.project_search_last != current {
+            self.run_project_search();
+        } else {
+            self.select_global_search_result();
+        }
+    }
+
+    /// Open the selected global search result in the active pane
+    pub fn select_global_search_result(&mut self) {
+        let Some(hit) = self
+            .global_search_results
+            .get(self.global_search_selected)
+            .cloned()
+        else {
+            return;
+        };
+        let query = self.global_search_textarea.lines().join("");
+        self.close_global_search();
+
+        match &hit.source {
+            HitSource::Buffer(idx) => {
+                if *idx != self.active_buffer {
+                    self.load_from_buffer(*idx);
+                }
+            }
+            HitSource::File(path) => {
+                if let Err(e) = self.load_file(path) {
+                    self.status_message = Some(format!("Error: {}", e));
+                    return;
+                }
+            }
+        }
+
+        self.show_search_hit(&query, self.global_search_options, hit.line, hit.start);
+    }
+
+    /// Start searching all markdown files under the current directory
+    fn run_project_search(&mut self) {
+        self.cancel_project_search();
+
+        let query = self.global_search_textarea.lines().join("");
+        let options = self.global_search_options;
+        self.global_search_results.clear();
+        self.global_search_selected = 0;
+        self.global_search_error = None;
+        self.project_search_last = Some((query.clone(), options));
+
+        if query.is_empty() {
+            return;
+        }
+
+        let pattern = match search::build_pattern(&query, options) {
+            Ok(re) => re,
+            Err(e) => {
+                self.global_search_error = Some(e);
+                return;
+            }
+        };
+
+        let (tx, rx) = self.waker.channel(Worker::ProjectSearch);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.project_search_receiver = Some(rx);
+        self.project_search_cancel = Some(cancel.clone());
+        self.project_search_running = true;
+
+        let theme = self.theme.clone();
+        std::thread::spawn(move || {
+            let paths = files::walk_markdown_files(Path::new("."));
+            search::search_project(paths, &pattern, &theme, &cancel, |message| {
+                tx.send(message).is_ok()
+            });
+        });
+    }
+
+    /// Stop a running project search
+    fn cancel_project_search(&mut self) {
+        if let Some(cancel) = self.project_search_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        self.project_search_receiver = None;
+        self.project_search_running = false;
+    }
+
+    /// Collect streamed results from a running project search
+    pub fn check_project_search(&mut self) {
+        let Some(rx) = &self.project_search_receiver else {
+            return;
+        };
+
+        loop {
+            match rx.try_recv() {
+                Ok(ProjectSearchMessage::Hits(hits)) => {
+                    self.global_search_results.extend(hits);
+                }
+                Ok(ProjectSearchMessage::Done(files)) => {
+                    self.status_message = Some(format!(
+                        "{} matches in {} files",
+                        self.global_search_results.len(),
+                        files
+                    ));
+                    self.project_search_receiver = None;
+                    self.project_search_cancel = None;
+                    self.project_search_running = false;
+                    return;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => return,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.project_search_receiver = None;
+                    self.project_search_cancel = None;
+                    self.project_search_running = false;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Recompute every pane's matches after the document changed, without
+    /// moving the view
+    fn refresh_search_matches(&mut self) {
+        for pane in &mut self.panes {
+            if pane.search_query.is_empty() {
+                continue;
+            }
+            let options = SearchOptions {
+                regex: pane.search_is_regex,
+                whole_word: pane.search_whole_word,
             };
+            let matches = search::build_pattern(&pane.search_query, options)
+                .map(|re| search::find_matches(&self.rendered_lines, &re))
+                .unwrap_or_default();
+            pane.view.current_match = pane.view.current_match.min(matches.len().saturating_sub(1));
+            pane.view.search_matches = matches;
+        }
+    }
+
+    /// Apply a search to the active pane and jump to a specific match
+    fn show_search_hit(&mut self, query: &str, options: SearchOptions, line: usize, start: usize) {
+        self.go_to_line(line);
+        let pane = &mut self.panes[self.active_pane];
+        pane.search_query = query.to_string();
+        pane.search_is_regex = options.regex;
+        pane.search_whole_word = options.whole_word;
+        pane.search_error = None;
+        if pane
+            .view
+            .search(&self.rendered_lines, query, options)
+            .is_err()
+        {
+            pane.view.clear_search();
+        }
+        if let Some(index) = pane
+            .view
+            .search_matches
+            .iter()
+            .position(|m| m.line == line && m.start == start)
+        {
+            pane.view.current_match = index;
+        }
+    }
+
+    // === Split view ===
+
+    /// Split vertically
+    pub fn split_vertical(&mut self) {
+        if self.split_direction == SplitDirection::None {
+            let new_pane = self.current_pane().clone_for_split();
+            self.panes.push(new_pane);
+            self.split_direction = SplitDirection::Vertical;
+            self.active_pane = 1;
+        }
+    }
+
+    /// Split horizontally
+    pub fn split_horizontal(&mut self) {
+        if self.split_direction == SplitDirection::None {
+            let new_pane = self.current_pane().clone_for_split();
+            self.panes.push(new_pane);
+            self.split_direction = SplitDirection::Horizontal;
+            self.active_pane = 1;
+        }
+    }
+
+    /// Close current pane
+    pub fn close_pane(&mut self) {
+        if self.panes.len() > 1 {
+            self.panes.remove(self.active_pane);
+            self.split_direction = SplitDirection::None;
+            self.split_ratio = 50;
+            self.active_pane = 0;
+        }
+    }
+
+    /// Cycle to next pane
+    pub fn cycle_pane(&mut self) {
+        if self.panes.len() > 1 {
+            self.active_pane = (self.active_pane + 1) % self.panes.len();
+        }
+    }
+
+    // === Mouse ===
+
+    /// Map a screen position in a pane to a (rendered line, column) position
+    pub fn content_position(&self, pane_idx: usize, x: u16, y: u16) -> Option<(usize, usize)> {
+        let area = *self.pane_text_areas.get(pane_idx)?;
+        if x < area.x || x >= area.right() || y < area.y || y >= area.bottom() {
+            return None;
+        }
+
+        let pane = self.panes.get(pane_idx)?;
+        let row = (y - area.y) as usize;
+        let col = (x - area.x) as usize;
+        let scroll = pane.view.scroll.min(self.line_count().saturating_sub(1));
+
+        if !self.line_wrap {
+            let line = scroll + row;
+            let column = view::scrolled_column(
+                self.rendered_lines.get(line)?,
+                pane.view.horizontal_scroll,
+                col,
+            )?;
+            return Some((line, column));
+        }
+
+        // Walk wrapped lines from the top of the viewport
+        let mut rows_used = 0;
+        for (line_idx, line) in self.rendered_lines.iter().enumerate().skip(scroll) {
+            let height = Paragraph::new(line.clone())
+                .wrap(Wrap { trim: false })
+                .line_count(area.width)
+                .max(1);
+            if row < rows_used + height {
+                let column = view::wrapped_column(line, area.width, row - rows_used, col)?;
+                return Some((line_idx, column));
+            }
+            rows_used += height;
+        }
+
+        None
+    }
+
+    /// Handle a left click in a content pane (follows links)
+    pub fn click_content(&mut self, pane_idx: usize, x: u16, y: u16) {
+        let Some((line, column)) = self.content_position(pane_idx, x, y) else {
+            return;
+        };
+
+        let link_url = self
+            .document
+            .as_ref()
+            .and_then(|doc| doc.link_at(line, column))
+            .map(|link| link.url.clone());
+
+        if let Some(url) = link_url {
+            self.open_link(&url);
+        }
+    }
+
+    /// Handle a left click in the outline panel (jumps to the heading)
+    pub fn click_outline(&mut self, y: u16) {
+        // First row inside the border is the first heading
+        let inner_top = self.outline_area.y + 1;
+        if y < inner_top {
+            return;
+        }
+
+        let index = (y - inner_top) as usize;
+        let heading_count = self.document.as_ref().map_or(0, |doc| doc.headings.len());
+        if index < heading_count {
+            self.outline_selected = index;
+            self.jump_to_heading();
+        }
+    }
+
+    /// Whether a pane currently draws a scrollbar
+    pub fn pane_has_scrollbar(&self, pane_idx: usize) -> bool {
+        self.pane_text_areas
+            .get(pane_idx)
+            .is_some_and(|area| self.line_count() > area.height as usize)
+    }
+
+    /// Scroll a pane so the scrollbar thumb sits at screen row `y`
+    pub fn scroll_to_scrollbar_row(&mut self, pane_idx: usize, y: u16) {
+        let Some(area) = self.pane_text_areas.get(pane_idx).copied() else {
+            return;
+        };
+
+        let max_scroll = self.line_count().saturating_sub(area.height as usize);
+        // Track excludes the begin/end arrows
+        let track_top = area.y + 1;
+        let track_len = area.height.saturating_sub(2).max(1);
+        let pos = y.clamp(track_top, track_top + track_len - 1) - track_top;
+
+        let scroll = if track_len <= 1 {
+            0
+        } else {
+            (pos as usize * max_scroll) / (track_len as usize - 1)
+        };
+
+        if let Some(pane) = self.panes.get_mut(pane_idx) {
+            pane.view.scroll = scroll;
+        }
+    }
+
+    /// Check whether a position is on the border between split panes
+    pub fn is_on_split_border(&self, x: u16, y: u16) -> bool {
+        if self.content_areas.len() < 2 {
+            return false;
+        }
+
+        let (first, second) = (self.content_areas[0], self.content_areas[1]);
+        match self.split_direction {
+            SplitDirection::Vertical => {
+                (x == first.right().saturating_sub(1) || x == second.x)
+                    && y >= first.y
+                    && y < first.bottom()
+            }
+            SplitDirection::Horizontal => {
+                (y == first.bottom().saturating_sub(1) || y == second.y)
+                    && x >= first.x
+                    && x < first.right()
+            }
+            SplitDirection::None => false,
         }
     }
 
+    /// Continue a mouse drag to the given position
+    pub fn drag_to(&mut self, x: u16, y: u16) {
+        match self.drag_target {
+            Some(DragTarget::OutlineBorder) => {
+                let width = (x + 1).saturating_sub(self.outline_area.x);
+                self.outline_width = width.clamp(16, 60);
+            }
+            Some(DragTarget::SplitBorder) => {
+                if self.content_areas.len() < 2 {
+                    return;
+                }
+                let (first, second) = (self.content_areas[0], self.content_areas[1]);
+                let (pos, start, len) = match self.split_direction {
+                    SplitDirection::Vertical => (x, first.x, second.right() - first.x),
+                    SplitDirection::Horizontal => (y, first.y, second.bottom() - first.y),
+                    SplitDirection::None => return,
+                };
+                if len > 0 {
+                    let ratio = (pos.saturating_sub(start) as u32 * 100 / len as u32) as u16;
+                    self.split_ratio = ratio.clamp(10, 90);
+                }
+            }
+            Some(DragTarget::Scrollbar(pane_idx)) => {
+                self.scroll_to_scrollbar_row(pane_idx, y);
+            }
+            None => {}
+        }
+    }
+
+    // === Display toggles ===
+
+    /// Toggle outline panel
+    pub fn toggle_outline(&mut self) {
+        self.show_outline = !self.show_outline;
+        if !self.show_outline && self.focused_panel == FocusedPanel::Outline {
+            self.focused_panel = FocusedPanel::Content;
+        }
+    }
+
+    /// Whether the left column (outline and/or file tree) is shown
+    pub fn sidebar_visible(&self) -> bool {
+        self.show_outline || self.show_file_tree
+    }
+
+    /// Toggle line wrap
+    pub fn toggle_line_wrap(&mut self) {
+        self.line_wrap = !self.line_wrap;
+    }
+
+    /// Toggle line numbers
+    pub fn toggle_line_numbers(&mut self) {
+        self.show_line_numbers = !self.show_line_numbers;
+    }
+
+    /// Cycle focus between panels (content, outline, file tree)
+    pub fn cycle_focus(&mut self) {
+        let order = [
+            (FocusedPanel::Content, true),
+            (FocusedPanel::Outline, self.show_outline),
+            (FocusedPanel::FileTree, self.show_file_tree),
+        ];
+        let current = order
+            .iter()
+            .position(|(panel, _)| *panel == self.focused_panel)
+            .unwrap_or(0);
+
+        self.focused_panel = (1..=order.len())
+            .map(|offset| order[(current + offset) % order.len()])
+            .find(|(_, shown)| *shown)
+            .map_or(FocusedPanel::Content, |(panel, _)| panel);
+    }
+
     /// Cycle to next theme
     pub fn cycle_theme(&mut self) {
         let themes = Theme::available_themes();
@@ -684,6 +1679,71 @@ impl AppState {
             .unwrap_or(0);
         let next_idx = (current_idx + 1) % themes.len();
         self.theme = Theme::by_name(themes[next_idx]);
+        self.apply_syntax_theme();
+    }
+
+    /// Syntax highlighting theme in use: the chosen one, or the theme's own
+    pub fn syntax_theme_name(&self) -> &str {
+        self.syntax_theme
+            .as_deref()
+            .unwrap_or(self.theme.syntax_theme)
+    }
+
+    /// Open the list of syntax highlighting themes, with the one in use selected
+    pub fn open_syntax_theme_picker(&mut self) {
+        let Some(highlighter) = &self.highlighter else {
+            self.status_message = Some("Syntax themes are still loading".to_string());
+            return;
+        };
+        self.syntax_theme_choices = highlighter
+            .available_themes()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        self.syntax_theme_selected = self
+            .syntax_theme
+            .as_ref()
+            .and_then(|current| self.syntax_theme_choices.iter().position(|n| n == current))
+            .map_or(0, |idx| idx + 1);
+        self.show_syntax_theme_picker = true;
+    }
+
+    /// Close the code theme picker
+    pub fn close_syntax_theme_picker(&mut self) {
+        self.show_syntax_theme_picker = false;
+    }
+
+    /// Move code theme picker selection up
+    pub fn syntax_theme_picker_up(&mut self) {
+        if self.syntax_theme_selected > 0 {
+            self.syntax_theme_selected -= 1;
+        }
+    }
+
+    /// Move code theme picker selection down
+    pub fn syntax_theme_picker_down(&mut self) {
+        if self.syntax_theme_selected < self.syntax_theme_choices.len() {
+            self.syntax_theme_selected += 1;
+        }
+    }
+
+    /// Use the selected code theme
+    pub fn select_syntax_theme(&mut self) {
+        self.syntax_theme = self
+            .syntax_theme_selected
+            .checked_sub(1)
+            .and_then(|idx| self.syntax_theme_choices.get(idx))
+            .cloned();
+        self.show_syntax_theme_picker = false;
+        self.apply_syntax_theme();
+    }
+
+    /// Re-render code blocks with the current syntax highlighting theme
+    fn apply_syntax_theme(&mut self) {
+        let name = self.syntax_theme_name().to_string();
+        if let Some(highlighter) = &mut self.highlighter {
+            highlighter.set_theme(&name);
+        }
         self.rerender();
     }
 
@@ -692,7 +1752,7 @@ impl AppState {
     /// Yank current line to clipboard
     pub fn yank_line(&mut self) {
         let pane = self.current_pane();
-        if let Some(line) = self.rendered_lines.get(pane.scroll) {
+        if let Some(line) = self.rendered_lines.get(pane.view.scroll) {
             let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
             if let Ok(mut clipboard) = arboard::Clipboard::new() {
                 if clipboard.set_text(&text).is_ok() {
@@ -715,10 +1775,17 @@ impl AppState {
             outline_width: self.outline_width,
             show_line_numbers: self.show_line_numbers,
             syntax_highlighting: self.syntax_highlighting,
+            syntax_theme: self.syntax_theme.clone(),
             auto_reload: self.auto_reload,
+            jump_to_change: self.jump_to_change,
+        };
+
+        let result = match &self.config_path {
+            Some(path) => config.save_to(path),
+            None => config.save(),
         };
 
-        match config.save() {
+        match result {
             Ok(()) => {
                 self.status_message = Some("Settings saved".to_string());
             }
@@ -728,54 +1795,230 @@ impl AppState {
         }
     }
 
+    // === File Tree ===
+
+    /// Toggle the file tree panel (scanning the current directory on first use)
+    pub fn toggle_file_tree(&mut self) {
+        if self.show_file_tree {
+            self.show_file_tree = false;
+            if self.focused_panel == FocusedPanel::FileTree {
+                self.focused_panel = FocusedPanel::Content;
+            }
+            return;
+        }
+
+        if self.file_tree.root.as_os_str().is_empty() {
+            let dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            self.open_file_tree(&dir);
+        } else {
+            self.show_file_tree = true;
+            self.focused_panel = FocusedPanel::FileTree;
+            self.reveal_current_file();
+        }
+    }
+
+    /// Show the file tree for a directory and focus it
+    pub fn open_file_tree(&mut self, root: &Path) {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        self.file_tree = FileTree::scan(&root);
+        self.file_tree_selected = 0;
+        self.file_tree_scroll = 0;
+        self.show_file_tree = true;
+        self.focused_panel = FocusedPanel::FileTree;
+        self.reveal_current_file();
+
+        if self.file_tree.is_empty() {
+            self.status_message = Some("No markdown files found".to_string());
+        }
+    }
+
+    /// Canonical path of the open document (if it's a local file)
+    pub fn current_file_canonical(&self) -> Option<PathBuf> {
+        if self.current_url.is_some() {
+            return None;
+        }
+        self.file_path
+            .as_ref()
+            .map(|p| p.canonicalize().unwrap_or_else(|_| p.clone()))
+    }
+
+    /// Expand the tree down to the open document and select it
+    pub fn reveal_current_file(&mut self) {
+        let Some(current) = self.current_file_canonical() else {
+            return;
+        };
+        self.file_tree.reveal(&current);
+        if let Some(idx) = self
+            .file_tree
+            .visible()
+            .iter()
+            .position(|entry| entry.path == current)
+        {
+            self.file_tree_selected = idx;
+        }
+    }
+
+    /// Move file tree selection up
+    pub fn file_tree_up(&mut self) {
+        if self.file_tree_selected > 0 {
+            self.file_tree_selected -= 1;
+        }
+    }
+
+    /// Move file tree selection down
+    pub fn file_tree_down(&mut self) {
+        let count = self.file_tree.visible().len();
+        if self.file_tree_selected + 1 < count {
+            self.file_tree_selected += 1;
+        }
+    }
+
+    /// Get the selected file tree entry
+    fn selected_tree_entry(&self) -> Option<files::TreeEntry> {
+        self.file_tree
+            .visible()
+            .get(self.file_tree_selected)
+            .map(|entry| (*entry).clone())
+    }
+
+    /// Open the selected file, or expand/collapse the selected directory
+    pub fn file_tree_activate(&mut self) {
+        let Some(entry) = self.selected_tree_entry() else {
+            return;
+        };
+
+        if entry.is_dir {
+            self.file_tree.toggle(&entry.path);
+        } else if let Err(e) = self.load_file(&entry.path) {
+            self.status_message = Some(format!("Error: {}", e));
+        }
+    }
+
+    /// Expand the selected directory, or open the selected file
+    pub fn file_tree_expand(&mut self) {
+        match self.selected_tree_entry() {
+            Some(entry) if entry.is_dir => self.file_tree.expand(&entry.path),
+            Some(_) => self.file_tree_activate(),
+            None => {}
+        }
+    }
+
+    /// Collapse the selected directory, or select the parent directory
+    pub fn file_tree_collapse(&mut self) {
+        let Some(entry) = self.selected_tree_entry() else {
+            return;
+        };
+
+        if entry.is_dir && self.file_tree.is_expanded(&entry.path) {
+            self.file_tree.collapse(&entry.path);
+            return;
+        }
+
+        let parent = entry.path.parent().map(Path::to_path_buf);
+        if let Some(idx) = self
+            .file_tree
+            .visible()
+            .iter()
+            .position(|e| e.is_dir && Some(&e.path) == parent.as_ref())
+        {
+            self.file_tree_selected = idx;
+        }
+    }
+
+    /// Handle a left click in the file tree panel
+    pub fn click_file_tree(&mut self, y: u16) {
+        // First row inside the border is the first visible entry
+        let inner_top = self.file_tree_area.y + 1;
+        if y < inner_top {
+            return;
+        }
+
+        let index = self.file_tree_scroll + (y - inner_top) as usize;
+        if index < self.file_tree.visible().len() {
+            self.file_tree_selected = index;
+            self.file_tree_activate();
+        }
+    }
+
     // === File Picker ===
 
-    /// Open the file picker, scanning current directory for .md files
+    /// Open the file picker on the current directory
     pub fn open_file_picker(&mut self) {
-        self.scan_directory(".");
-        if !self.file_picker_files.is_empty() {
+        let dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        self.scan_directory(&dir);
+        if !self.file_picker_files.is_empty() || !self.file_picker_dirs.is_empty() {
             self.show_file_picker = true;
-            self.file_picker_selected = 0;
+            self.update_preview();
         } else {
             self.status_message = Some("No markdown files found".to_string());
         }
     }
 
-    /// Scan a directory for markdown files
-    pub fn scan_directory(&mut self, path: &str) {
-        self.file_picker_files.clear();
+    /// Scan a directory tree for markdown files and reset the filter
+    pub fn scan_directory(&mut self, path: &Path) {
+        self.file_picker_dir = path.to_path_buf();
+        self.file_picker_files = files::scan_markdown_files(path);
+        self.file_picker_dirs = files::list_subdirectories(path);
+        self.file_picker_textarea = TextArea::default();
+        self.filter_file_picker();
+    }
 
-        if let Ok(entries) = std::fs::read_dir(path) {
-            let mut files: Vec<PathBuf> = entries
-                .filter_map(|e| e.ok())
-                .map(|e| e.path())
-                .filter(|p| {
-                    p.is_file()
-                        && p.extension()
-                            .map(|ext| ext.eq_ignore_ascii_case("md"))
-                            .unwrap_or(false)
+    /// Rebuild the picker entries from the filter text.
+    ///
+    /// With an empty filter, lists `../`, subdirectories, then every markdown
+    /// file in the tree. Otherwise fuzzy-matches all of them, best first.
+    pub fn filter_file_picker(&mut self) {
+        let query = self.file_picker_textarea.lines().join("");
+
+        let candidates = self
+            .file_picker_dirs
+            .iter()
+            .map(|dir| (dir, true))
+            .chain(self.file_picker_files.iter().map(|file| (file, false)));
+
+        let mut entries: Vec<PickerEntry> = if query.is_empty() {
+            let parent = self.file_picker_dir.parent().map(|parent| PickerEntry {
+                path: parent.to_path_buf(),
+                display: "../".to_string(),
+                is_dir: true,
+                matched: Vec::new(),
+            });
+            parent
+                .into_iter()
+                .chain(candidates.map(|(path, is_dir)| self.picker_entry(path, is_dir)))
+                .collect()
+        } else {
+            let mut scored: Vec<(i64, PickerEntry)> = candidates
+                .filter_map(|(path, is_dir)| {
+                    let mut entry = self.picker_entry(path, is_dir);
+                    let (score, matched) = files::fuzzy_match(&query, &entry.display)?;
+                    entry.matched = matched;
+                    Some((score, entry))
                 })
                 .collect();
+            // Stable sort keeps directory/README/alphabetical order among ties
+            scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            scored.into_iter().map(|(_, entry)| entry).collect()
+        };
 
-            // Sort alphabetically, but put README files first
-            files.sort_by(|a, b| {
-                let a_is_readme = a
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_lowercase().starts_with("readme"))
-                    .unwrap_or(false);
-                let b_is_readme = b
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_lowercase().starts_with("readme"))
-                    .unwrap_or(false);
-
-                match (a_is_readme, b_is_readme) {
-                    (true, false) => std::cmp::Ordering::Less,
-                    (false, true) => std::cmp::Ordering::Greater,
-                    _ => a.cmp(b),
-                }
-            });
+        entries.truncate(MAX_PICKER_ENTRIES);
+        self.file_picker_entries = entries;
+        self.file_picker_selected = 0;
+        self.update_preview();
+    }
 
-            self.file_picker_files = files;
+    /// Build a picker entry displayed relative to the picker directory
+    fn picker_entry(&self, path: &Path, is_dir: bool) -> PickerEntry {
+        let relative = path.strip_prefix(&self.file_picker_dir).unwrap_or(path);
+        let mut display = relative.to_string_lossy().to_string();
+        if is_dir {
+            display.push('/');
+        }
+        PickerEntry {
+            path: path.to_path_buf(),
+            display,
+            is_dir,
+            matched: Vec::new(),
         }
     }
 
@@ -783,30 +2026,46 @@ impl AppState {
     pub fn file_picker_up(&mut self) {
         if self.file_picker_selected > 0 {
             self.file_picker_selected -= 1;
+            self.update_preview();
         }
     }
 
     /// Move file picker selection down
     pub fn file_picker_down(&mut self) {
-        if !self.file_picker_files.is_empty() {
-            let max = self.file_picker_files.len() - 1;
+        if !self.file_picker_entries.is_empty() {
+            let max = self.file_picker_entries.len() - 1;
             if self.file_picker_selected < max {
                 self.file_picker_selected += 1;
+                self.update_preview();
             }
         }
     }
 
-    /// Open the selected file from the picker
+    /// Open the selected file, or descend into the selected directory
     pub fn open_selected_file(&mut self) {
-        if let Some(path) = self
-            .file_picker_files
+        let Some(entry) = self
+            .file_picker_entries
             .get(self.file_picker_selected)
             .cloned()
-        {
-            self.show_file_picker = false;
-            if let Err(e) = self.load_file(&path) {
-                self.status_message = Some(format!("Error: {}", e));
-            }
+        else {
+            return;
+        };
+
+        if entry.is_dir {
+            self.scan_directory(&entry.path);
+            return;
+        }
+
+        self.show_file_picker = false;
+        if let Err(e) = self.load_file(&entry.path) {
+            self.status_message = Some(format!("Error: {}", e));
+        }
+    }
+
+    /// Move the picker up to the parent directory
+    pub fn file_picker_parent(&mut self) {
+        if let Some(parent) = self.file_picker_dir.parent().map(Path::to_path_buf) {
+            self.scan_directory(&parent);
         }
     }
 
@@ -819,7 +2078,7 @@ impl AppState {
 
     /// Follow the link on the current line (if any)
     pub fn follow_link(&mut self) {
-        let current_line = self.current_pane().scroll;
+        let current_line = self.current_pane().view.scroll;
 
         let link_url = if let Some(doc) = &self.document {
             doc.link_at_line(current_line).map(|l| l.url.clone())
@@ -880,23 +2139,14 @@ impl AppState {
                 }
             }
         } else if let Some(anchor) = url.strip_prefix('#') {
-            // Anchor link - try to find heading
-            let heading_info = if let Some(doc) = &self.document {
-                // Find heading that matches anchor (simplified slug matching)
-                let target = anchor.to_lowercase().replace('-', " ");
-                doc.headings
-                    .iter()
-                    .find(|h| {
-                        h.text.to_lowercase() == target
-                            || h.text.to_lowercase().replace(' ', "-") == anchor.to_lowercase()
-                    })
-                    .map(|h| (h.line_number, h.text.clone()))
-            } else {
-                None
-            };
-
-            if let Some((line, text)) = heading_info {
-                self.go_to_line(line);
+            // Anchor link - resolve it against the headings' GitHub-style slugs
+            if self.go_to_heading(url) {
+                let text = self
+                    .document
+                    .as_ref()
+                    .and_then(|doc| doc.headings.get(self.outline_selected))
+                    .map(|h| h.text.clone())
+                    .unwrap_or_default();
                 self.status_message = Some(format!("Jumped to: {}", text));
             } else {
                 self.status_message = Some(format!("Anchor not found: {}", anchor));
@@ -908,34 +2158,41 @@ impl AppState {
 
     // === Buffer Management ===
 
-    /// Save current document state to its buffer
+    /// Whether the view is showing `buffer`. URL buffers only match by URL,
+    /// since their paths are placeholders.
+    fn is_current_buffer(&self, buffer: &DocumentBuffer) -> bool {
+        match &self.current_url {
+            Some(url) => buffer.url.as_ref() == Some(url),
+            None => buffer.url.is_none() && self.file_path.as_ref() == Some(&buffer.file_path),
+        }
+    }
+
+    /// Save current document state to its buffer, adding one if needed.
+    /// The document itself stays with the view until `park_current_buffer`.
     fn save_to_buffer(&mut self) {
-        if self.document.is_none() || self.file_path.is_none() {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        if self.document.is_none() {
             return;
         }
-
-        let doc = self.document.as_ref().unwrap();
-        let path = self.file_path.as_ref().unwrap();
         let pane = self.current_pane();
 
         let buffer = DocumentBuffer {
-            document: doc.clone(),
+            content: None,
+            stale: false,
             file_path: path.clone(),
             url: self.current_url.clone(),
-            rendered_lines: self.rendered_lines.clone(),
-            scroll: pane.scroll,
-            horizontal_scroll: pane.horizontal_scroll,
+            scroll: pane.view.scroll,
+            horizontal_scroll: pane.view.horizontal_scroll,
             outline_selected: self.outline_selected,
             modified_time: self.file_modified_time,
+            changed_on_disk: false,
+            deleted: self.file_deleted,
         };
 
-        // Check if buffer already exists for this file
-        // Skip URL buffers when searching by path to avoid false matches
-        if let Some(idx) = self
-            .buffers
-            .iter()
-            .position(|b| b.url.is_none() && b.file_path == *path)
-        {
+        // Check if buffer already exists for this file or URL
+        if let Some(idx) = self.buffers.iter().position(|b| self.is_current_buffer(b)) {
             self.buffers[idx] = buffer;
             self.active_buffer = idx;
         } else {
@@ -944,41 +2201,81 @@ impl AppState {
         }
     }
 
+    /// Move the current document into its buffer, leaving the view empty for
+    /// the next document
+    fn park_current_buffer(&mut self) {
+        self.save_to_buffer();
+        if let Some(document) = self.document.take() {
+            let rendered_lines = std::mem::take(&mut self.rendered_lines);
+            if let Some(buffer) = self.buffers.get_mut(self.active_buffer)
+                && buffer.content.is_none()
+            {
+                buffer.content = Some((document, rendered_lines));
+            }
+        }
+    }
+
+    /// A buffer's document and rendered lines, wherever they are held
+    fn buffer_content<'a>(
+        &'a self,
+        buffer: &'a DocumentBuffer,
+    ) -> Option<(&'a Document, &'a [ratatui::text::Line<'static>])> {
+        match &buffer.content {
+            Some((document, rendered_lines)) => Some((document, rendered_lines)),
+            None => self
+                .document
+                .as_ref()
+                .map(|document| (document, &self.rendered_lines[..])),
+        }
+    }
+
     /// Load a buffer into the current view
-    fn load_from_buffer(&mut self, index: usize) {
+    pub fn load_from_buffer(&mut self, index: usize) {
         if index >= self.buffers.len() {
             return;
         }
 
-        // First save current document state
-        self.save_to_buffer();
+        // First put the current document back in its buffer
+        self.park_current_buffer();
 
-        // Clone all values we need from the buffer first
-        let buffer = &self.buffers[index];
-        let document = buffer.document.clone();
+        let buffer = &mut self.buffers[index];
+        let content = buffer.content.take();
+        let stale = std::mem::take(&mut buffer.stale);
         let file_path = buffer.file_path.clone();
         let url = buffer.url.clone();
-        let rendered_lines = buffer.rendered_lines.clone();
         let outline_selected = buffer.outline_selected;
         let scroll = buffer.scroll;
         let horizontal_scroll = buffer.horizontal_scroll;
         let modified_time = buffer.modified_time;
+        let changed_on_disk = std::mem::take(&mut buffer.changed_on_disk);
+        let deleted = buffer.deleted;
 
         // Now apply them
-        self.document = Some(document);
+        if let Some((mut document, mut rendered_lines)) = content {
+            if stale {
+                rendered_lines = self.render_document(&mut document);
+            }
+            self.document = Some(document);
+            self.rendered_lines = rendered_lines;
+        }
         self.file_path = Some(file_path);
         self.current_url = url;
-        self.rendered_lines = rendered_lines;
         self.outline_selected = outline_selected;
         self.file_modified_time = modified_time;
+        self.file_deleted = deleted;
         self.is_loading = false;
 
         // Restore scroll position
         let pane = self.current_pane_mut();
-        pane.scroll = scroll;
-        pane.horizontal_scroll = horizontal_scroll;
+        pane.view.scroll = scroll;
+        pane.view.horizontal_scroll = horizontal_scroll;
 
         self.active_buffer = index;
+
+        // Catch up with changes made while the buffer was in the background
+        if changed_on_disk && self.auto_reload {
+            self.reload_current_file();
+        }
     }
 
     /// Open buffer list overlay
@@ -1051,7 +2348,16 @@ impl AppState {
             return;
         }
 
-        self.buffers.remove(self.active_buffer);
+        // Drop the shown document with its buffer, so it is not parked again
+        self.save_to_buffer();
+        let closed = self.buffers.remove(self.active_buffer);
+        if closed.url.is_none()
+            && let Some(watcher) = &mut self.file_watcher
+        {
+            watcher.unwatch(&closed.file_path);
+        }
+        self.document = None;
+        self.rendered_lines.clear();
         if self.active_buffer >= self.buffers.len() {
             self.active_buffer = self.buffers.len() - 1;
         }
@@ -1073,7 +2379,7 @@ impl AppState {
         self.is_loading = true;
         self.status_message = Some(format!("Loading {}...", url));
 
-        let (tx, rx) = std::sync::mpsc::channel();
+        let (tx, rx) = self.waker.channel(Worker::Fetch);
         self.fetch_receiver = Some(rx);
 
         let fetcher = self.github_fetcher.clone();
@@ -1088,8 +2394,294 @@ impl AppState {
         });
     }
 
+    /// Open a URL as a new buffer without switching to it.
+    ///
+    /// The buffer shows a placeholder until the background fetch finishes.
+    pub fn open_url_buffer(&mut self, url: &str) {
+        let display_name = url.rsplit('/').next().unwrap_or(url).to_string();
+        let placeholder = format!("# Loading\n\n{}", url);
+        let (document, rendered_lines) = self.render_content(&placeholder);
+
+        self.buffers.push(DocumentBuffer {
+            content: Some((document, rendered_lines)),
+            stale: false,
+            file_path: PathBuf::from(format!("[URL] {}", display_name)),
+            url: Some(url.to_string()),
+            scroll: 0,
+            horizontal_scroll: 0,
+            outline_selected: 0,
+            modified_time: None,
+            changed_on_disk: false,
+            deleted: false,
+        });
+
+        let (tx, rx) = self.waker.channel(Worker::Fetch);
+        self.buffer_fetches.push((url.to_string(), rx));
+
+        let fetcher = self.github_fetcher.clone();
+        let url_owned = url.to_string();
+
+        std::thread::spawn(move || {
+            let result = fetcher
+                .fetch(&url_owned)
+                .map(|content| (content, url_owned.clone()))
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    /// Parse and render markdown content with the current settings
+    fn render_content(&self, content: &str) -> RenderedDocument {
+        let mut document = Document::parse(content);
+        let rendered_lines = self.render_document(&mut document);
+        (document, rendered_lines)
+    }
+
+    /// Render a parsed document with the current settings. Code blocks are
+    /// syntax highlighted lazily by `highlight_visible_code`.
+    fn render_document(&self, document: &mut Document) -> Vec<ratatui::text::Line<'static>> {
+        if self.syntax_highlighting {
+            document.render_deferred(&self.theme)
+        } else {
+            document.render_with_highlighting(&self.theme, None)
+        }
+    }
+
+    /// Load the syntax highlighter on a background thread, unless it is
+    /// already loaded or loading
+    fn load_highlighter(&mut self) {
+        if self.highlighter.is_some() || self.highlighter_receiver.is_some() {
+            return;
+        }
+        let (tx, rx) = self.waker.channel(Worker::Highlighter);
+        self.highlighter_receiver = Some(rx);
+        std::thread::spawn(move || {
+            let _ = tx.send(syntaxes::load_highlighter());
+        });
+    }
+
+    /// Take the syntax highlighter once it has loaded
+    pub fn check_highlighter(&mut self) {
+        let Some(rx) = &self.highlighter_receiver else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok((mut highlighter, error)) => {
+                if error.is_some() {
+                    self.status_message = error;
+                }
+                highlighter.set_theme(self.syntax_theme_name());
+                self.highlighter = Some(highlighter);
+                self.highlighter_receiver = None;
+                // Previews were rendered without it
+                self.preview_cache.clear();
+                self.update_preview();
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => self.highlighter_receiver = None,
+        }
+    }
+
+    /// Block until the syntax highlighter has loaded, if it is loading
+    fn wait_for_highlighter(&mut self) {
+        if let Some(rx) = self.highlighter_receiver.take()
+            && let Ok((mut highlighter, _)) = rx.recv()
+        {
+            highlighter.set_theme(self.syntax_theme_name());
+            self.highlighter = Some(highlighter);
+        }
+    }
+
+    /// Syntax highlight the code blocks in and around each pane's view
+    pub fn highlight_visible_code(&mut self) {
+        let (Some(document), Some(highlighter)) = (&mut self.document, &self.highlighter) else {
+            return;
+        };
+        if document.unhighlighted_code.is_empty() {
+            return;
+        }
+
+        for (i, pane) in self.panes.iter().enumerate() {
+            let height = self
+                .pane_text_areas
+                .get(i)
+                .map_or(HIGHLIGHT_MARGIN, |area| area.height as usize);
+            let range = pane.view.scroll.saturating_sub(HIGHLIGHT_MARGIN)
+                ..pane.view.scroll + height + HIGHLIGHT_MARGIN;
+            document.highlight_lines(&mut self.rendered_lines, range, &self.theme, highlighter);
+        }
+    }
+
+    /// Parse and render a large file on a background thread
+    fn start_render_job(&mut self, path: PathBuf, content: String, reload: bool) {
+        let (tx, receiver) = self.waker.channel(Worker::Render);
+        self.render_jobs.retain(|job| job.path != path);
+        self.render_jobs.push(RenderJob {
+            path,
+            reload,
+            receiver,
+        });
+        self.spawn_render(content, tx);
+    }
+
+    /// Parse and render `content` with the current settings on a new thread,
+    /// sending the result to `tx`
+    fn spawn_render(&self, content: String, tx: WakingSender<RenderedDocument>) {
+        let theme = self.theme.clone();
+        let deferred = self.syntax_highlighting;
+        std::thread::spawn(move || {
+            let mut document = Document::parse(&content);
+            let lines = if deferred {
+                document.render_deferred(&theme)
+            } else {
+                document.render_with_highlighting(&theme, None)
+            };
+            let _ = tx.send((document, lines));
+        });
+    }
+
+    /// Whether the current document is still rendering in the background
+    pub fn is_rendering(&self) -> bool {
+        self.current_url.is_none()
+            && self
+                .render_jobs
+                .iter()
+                .any(|job| Some(&job.path) == self.file_path.as_ref())
+    }
+
+    /// Swap in large documents that finished rendering in the background
+    pub fn check_render_jobs(&mut self) {
+        let mut finished = Vec::new();
+        self.render_jobs
+            .retain(|job| match job.receiver.try_recv() {
+                Ok(result) => {
+                    finished.push((job.path.clone(), job.reload, result));
+                    false
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => true,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => false,
+            });
+
+        for (path, reload, result) in finished {
+            if self.current_url.is_none() && self.file_path.as_ref() == Some(&path) {
+                if reload {
+                    self.finish_reload(path, result);
+                } else {
+                    self.finish_render(result);
+                }
+            } else if let Some(buffer) = self
+                .buffers
+                .iter_mut()
+                .find(|b| b.url.is_none() && b.file_path == path)
+            {
+                buffer.content = Some(result);
+            }
+        }
+    }
+
+    /// Block until the current document has finished rendering, if it is
+    fn wait_for_render(&mut self) {
+        if !self.is_rendering() {
+            return;
+        }
+        let Some(index) = self
+            .render_jobs
+            .iter()
+            .position(|job| Some(&job.path) == self.file_path.as_ref())
+        else {
+            return;
+        };
+        let job = self.render_jobs.remove(index);
+        if let Ok(result) = job.receiver.recv() {
+            if job.reload {
+                self.finish_reload(job.path, result);
+            } else {
+                self.finish_render(result);
+            }
+        }
+    }
+
+    /// Swap in the fully rendered current document
+    fn finish_render(&mut self, (document, rendered_lines): RenderedDocument) {
+        // The first screen renders the same, so the scroll position holds
+        self.document = Some(document);
+        self.rendered_lines = rendered_lines;
+        self.refresh_search_matches();
+        self.save_to_buffer();
+        if let Some(jump) = self.startup_jump.take() {
+            self.apply_startup_jump(jump);
+        }
+    }
+
+    /// Poll background URL buffer fetches and fill in finished buffers
+    fn check_buffer_fetches(&mut self) {
+        let mut finished = Vec::new();
+        self.buffer_fetches.retain(|(url, rx)| match rx.try_recv() {
+            Ok(result) => {
+                finished.push((url.clone(), result));
+                false
+            }
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                finished.push((url.clone(), Err("fetch failed unexpectedly".to_string())));
+                false
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => true,
+        });
+
+        for (url, result) in finished {
+            self.finish_buffer_fetch(&url, result);
+        }
+    }
+
+    /// Replace a URL buffer's placeholder with fetched content (or the error)
+    fn finish_buffer_fetch(&mut self, url: &str, result: FetchResult) {
+        let display_name = url.rsplit('/').next().unwrap_or(url).to_string();
+        let content = match result {
+            Ok((content, _)) => {
+                self.history.add(url, true, &display_name);
+                content
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Error loading {}: {}", url, e));
+                format!("# Failed to load\n\n{}\n\n{}", url, e)
+            }
+        };
+
+        let Some(idx) = self
+            .buffers
+            .iter()
+            .position(|b| b.url.as_deref() == Some(url))
+        else {
+            return;
+        };
+
+        let (document, rendered_lines) = self.render_content(&content);
+
+        // Update the view too if this buffer is being shown
+        if idx == self.active_buffer && self.current_url.as_deref() == Some(url) {
+            self.document = Some(document);
+            self.rendered_lines = rendered_lines;
+            self.outline_selected = 0;
+            for pane in &mut self.panes {
+                pane.view.scroll = 0;
+                pane.view.horizontal_scroll = 0;
+                pane.view.search_matches.clear();
+            }
+        } else {
+            self.buffers[idx].content = Some((document, rendered_lines));
+            self.buffers[idx].stale = false;
+        }
+
+        let buffer = &mut self.buffers[idx];
+        buffer.scroll = 0;
+        buffer.horizontal_scroll = 0;
+        buffer.outline_selected = 0;
+    }
+
     /// Check if async fetch is complete and process result
     pub fn check_fetch_complete(&mut self) {
+        self.check_buffer_fetches();
+
         if let Some(rx) = &self.fetch_receiver {
             match rx.try_recv() {
                 Ok(result) => {
@@ -1116,18 +2708,17 @@ impl AppState {
                 }
             }
         }
+
+        // A URL being previewed may have just been fetched
+        self.update_preview();
     }
 
     /// Finish loading URL content (after fetch completes)
     fn finish_load_url(&mut self, content: &str, url: &str) {
         // Parse and render
-        let mut document = Document::parse(content);
-        let highlighter = if self.syntax_highlighting {
-            Some(&self.highlighter)
-        } else {
-            None
-        };
-        self.rendered_lines = document.render_with_highlighting(&self.theme, highlighter);
+        let (document, rendered_lines) = self.render_content(content);
+        self.park_current_buffer();
+        self.rendered_lines = rendered_lines;
 
         // Extract display name from URL
         let display_name = url.rsplit('/').next().unwrap_or(url).to_string();
@@ -1140,12 +2731,13 @@ impl AppState {
         self.file_path = Some(placeholder_path);
         self.current_url = Some(url.to_string());
         self.file_modified_time = None; // No auto-reload for URLs
+        self.file_deleted = false;
 
         // Reset pane state
         for pane in &mut self.panes {
-            pane.scroll = 0;
-            pane.horizontal_scroll = 0;
-            pane.search_matches.clear();
+            pane.view.scroll = 0;
+            pane.view.horizontal_scroll = 0;
+            pane.view.search_matches.clear();
         }
 
         self.outline_selected = 0;
@@ -1155,6 +2747,10 @@ impl AppState {
         let _ = self.history.save();
 
         self.status_message = Some(format!("Loaded: {}", display_name));
+
+        if let Some(jump) = self.startup_jump.take() {
+            self.apply_startup_jump(jump);
+        }
     }
 
     /// Load URL content (blocking - kept for potential future use)
@@ -1189,6 +2785,15 @@ impl AppState {
         self.current_url.is_some()
     }
 
+    /// Check if currently viewing a document read from stdin
+    pub fn is_viewing_stdin(&self) -> bool {
+        self.current_url.is_none()
+            && self
+                .file_path
+                .as_ref()
+                .is_some_and(|p| p.as_os_str() == STDIN_PATH)
+    }
+
     // === URL Input Mode ===
 
     /// Start URL input mode
@@ -1218,6 +2823,377 @@ impl AppState {
         self.show_url_input = false;
     }
 
+    // === Command Mode ===
+
+    /// Start typing a `:` command
+    pub fn start_command(&mut self) {
+        self.mode = InputMode::Command;
+        self.command_textarea = TextArea::default();
+    }
+
+    /// Run the typed command
+    pub fn submit_command(&mut self) {
+        let command = self.command_textarea.lines().join("");
+        self.mode = InputMode::Normal;
+        self.run_command(command.trim());
+    }
+
+    /// Cancel command input
+    pub fn cancel_command(&mut self) {
+        self.mode = InputMode::Normal;
+    }
+
+    /// Run a command: `w [FILE]`, `export FORMAT [FILE]` or `q`. A `!` after
+    /// `w` or `export` overwrites an existing file.
+    pub fn run_command(&mut self, command: &str) {
+        let mut words = command.split_whitespace();
+        let Some(name) = words.next() else {
+            return;
+        };
+        let (name, force) = match name.strip_suffix('!') {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+        match name {
+            "q" | "quit" => self.should_quit = true,
+            "w" | "write" => {
+                let path = words.next().map(PathBuf::from);
+                let format = path
+                    .as_deref()
+                    .map_or(ExportFormat::Html, ExportFormat::from_path);
+                self.export_current(format, path, force);
+            }
+            "export" => match words.next().map(ExportFormat::from_name) {
+                Some(Some(format)) => {
+                    let path = words.next().map(PathBuf::from);
+                    self.export_current(format, path, force);
+                }
+                Some(None) => {
+                    self.status_message =
+                        Some("Export format must be html, text or ansi".to_string());
+                }
+                None => self.status_message = Some("Usage: export FORMAT [FILE]".to_string()),
+            },
+            other => self.status_message = Some(format!("Unknown command: {}", other)),
+        }
+    }
+
+    /// Write the current document to a file in the given format. Without a
+    /// path, the document's name with the format's extension is used. An
+    /// existing file is only replaced when `force` is set.
+    pub fn export_current(&mut self, format: ExportFormat, path: Option<PathBuf>, force: bool) {
+        if self.document.is_none() {
+            self.status_message = Some("No document to export".to_string());
+            return;
+        }
+
+        let title = match (&self.current_url, &self.file_path) {
+            (Some(url), _) => url.clone(),
+            (None, Some(path)) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string()),
+            (None, None) => "barkdocs".to_string(),
+        };
+        let path = path.unwrap_or_else(|| {
+            let name = match &self.current_url {
+                Some(url) => Path::new(url.trim_end_matches('/').rsplit('/').next().unwrap_or(url)),
+                None if self.is_viewing_stdin() => Path::new("stdin"),
+                None => self.file_path.as_deref().unwrap_or(Path::new("barkdocs")),
+            };
+            let stem = name
+                .file_stem()
+                .map_or("barkdocs".into(), |s| s.to_string_lossy());
+            PathBuf::from(format!("{}.{}", stem, format.extension()))
+        });
+
+        // Never overwrite the markdown being viewed
+        let same_file = self.file_path.as_ref().is_some_and(|current| {
+            current == &path
+                || matches!(
+                    (current.canonicalize(), path.canonicalize()),
+                    (Ok(a), Ok(b)) if a == b
+                )
+        });
+        if same_file {
+            self.status_message = Some("Refusing to overwrite the document itself".to_string());
+            return;
+        }
+        // Exports are never markdown, so a markdown target is a mistake
+        if files::is_markdown_file(&path) {
+            self.status_message = Some(format!(
+                "Refusing to export to markdown file {}",
+                path.display()
+            ));
+            return;
+        }
+        if !force && path.exists() {
+            self.status_message = Some(format!(
+                "{} exists (use :w! or :export! to overwrite)",
+                path.display()
+            ));
+            return;
+        }
+
+        // Exports include the whole document, every code block highlighted
+        self.wait_for_render();
+        self.wait_for_highlighter();
+        if let (Some(document), Some(highlighter)) = (&mut self.document, &self.highlighter) {
+            document.highlight_lines(
+                &mut self.rendered_lines,
+                0..usize::MAX,
+                &self.theme,
+                highlighter,
+            );
+        }
+        let Some(document) = &self.document else {
+            return;
+        };
+        let content = export::export(
+            format,
+            document,
+            &self.rendered_lines,
+            &self.theme,
+            &title,
+            export::DEFAULT_WIDTH,
+        );
+        self.status_message = Some(match std::fs::write(&path, content) {
+            Ok(()) => format!("Exported to {}", path.display()),
+            Err(e) => format!("Export failed: {}", e),
+        });
+    }
+
+    // === Follow Mode ===
+
+    /// Stream stdin into a `<stdin>` document, re-rendering as text arrives
+    pub fn start_follow_stdin(&mut self) {
+        self.load_stdin("");
+        let (tx, rx) = self.waker.channel(Worker::Follow);
+        follow::follow_stdin(tx);
+        self.start_follow(PathBuf::from(STDIN_PATH), rx);
+    }
+
+    /// Follow a file like `tail -f`, re-rendering as it grows
+    pub fn start_follow_file(&mut self, path: &Path) {
+        let (tx, rx) = self.waker.channel(Worker::Follow);
+        follow::follow_file(path.to_path_buf(), tx);
+        self.start_follow(path.to_path_buf(), rx);
+    }
+
+    fn start_follow(&mut self, path: PathBuf, rx: Receiver<FollowMessage>) {
+        self.follow_receiver = Some(rx);
+        self.follow_path = Some(path);
+        self.follow_content.clear();
+        self.follow_render = None;
+        self.follow_dirty = false;
+        self.status_message = Some("Following (scroll up to pause, G to resume)".to_string());
+    }
+
+    /// Whether the view shows the followed document
+    pub fn is_following_current(&self) -> bool {
+        self.follow_receiver.is_some()
+            && self.current_url.is_none()
+            && self.follow_path.is_some()
+            && self.follow_path == self.file_path
+    }
+
+    /// Scroll offset that puts the end of the document at the bottom of the active pane
+    fn bottom_scroll(&self) -> usize {
+        let Some(area) = self.pane_text_areas.get(self.active_pane) else {
+            return 0;
+        };
+        let height = area.height as usize;
+
+        // Walk back from the end, counting wrapped rows when wrap is on
+        let mut rows = 0;
+        for (idx, line) in self.rendered_lines.iter().enumerate().rev() {
+            rows += if self.line_wrap {
+                Paragraph::new(line.clone())
+                    .wrap(Wrap { trim: false })
+                    .line_count(area.width)
+                    .max(1)
+            } else {
+                1
+            };
+            if rows > height {
+                return idx + 1;
+            }
+        }
+        0
+    }
+
+    /// Apply text streamed by the follow worker. The text is rendered on a
+    /// background thread, one render at a time, so bursts of output are
+    /// coalesced instead of re-parsing the document for every chunk.
+    pub fn check_follow(&mut self) {
+        let mut ended = None;
+        if let Some(rx) = &self.follow_receiver {
+            loop {
+                match rx.try_recv() {
+                    Ok(FollowMessage::Append(text)) => {
+                        self.follow_content.push_str(&text);
+                        self.follow_dirty = true;
+                    }
+                    Ok(FollowMessage::Replace(text)) => {
+                        self.follow_content = text;
+                        self.follow_dirty = true;
+                    }
+                    Ok(FollowMessage::Error(e)) => {
+                        ended = Some(format!("Follow stopped: {}", e));
+                        break;
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        ended = Some("End of input".to_string());
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(rx) = &self.follow_render {
+            match rx.try_recv() {
+                Ok(rendered) => {
+                    self.follow_render = None;
+                    self.apply_follow_render(rendered);
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => self.follow_render = None,
+            }
+        }
+        if self.follow_dirty && self.follow_render.is_none() {
+            let (tx, rx) = self.waker.channel(Worker::Follow);
+            self.follow_render = Some(rx);
+            self.follow_dirty = false;
+            self.spawn_render(self.follow_content.clone(), tx);
+        }
+
+        if let Some(message) = ended {
+            self.follow_receiver = None;
+            self.status_message = Some(message);
+        }
+    }
+
+    /// Show a render of the followed text, keeping the view pinned to the
+    /// bottom unless the user scrolled up
+    fn apply_follow_render(&mut self, (document, rendered_lines): RenderedDocument) {
+        if self.current_url.is_none() && self.follow_path == self.file_path {
+            let pinned = self.current_pane().view.scroll >= self.bottom_scroll();
+            self.document = Some(document);
+            self.rendered_lines = rendered_lines;
+
+            if pinned {
+                let bottom = self.bottom_scroll();
+                self.current_pane_mut().view.scroll = bottom;
+            }
+            self.refresh_search_matches();
+        } else if let Some(buffer) = self
+            .buffers
+            .iter_mut()
+            .find(|b| b.url.is_none() && Some(&b.file_path) == self.follow_path.as_ref())
+        {
+            buffer.content = Some((document, rendered_lines));
+            buffer.stale = false;
+        }
+    }
+
+    // === Overlay Preview ===
+
+    /// Location (path or URL) highlighted in the open list overlay
+    fn selected_overlay_location(&self) -> Option<(String, bool)> {
+        if self.show_file_picker {
+            let entry = self.file_picker_entries.get(self.file_picker_selected)?;
+            (!entry.is_dir).then(|| (entry.path.to_string_lossy().to_string(), false))
+        } else if self.show_history {
+            let entry = self.history.entries().get(self.history_selected)?;
+            Some((entry.location.clone(), entry.is_url))
+        } else if self.show_bookmarks {
+            let bookmark = self.bookmarks.entries().get(self.bookmarks_selected)?;
+            Some((bookmark.location.clone(), bookmark.is_url))
+        } else {
+            None
+        }
+    }
+
+    /// Render (or reuse) the preview for the highlighted overlay entry.
+    ///
+    /// Files are re-rendered when their modification time changes; URLs are
+    /// only previewed if they were already fetched this session.
+    pub fn update_preview(&mut self) {
+        let Some((location, is_url)) = self.selected_overlay_location() else {
+            self.preview_location = None;
+            return;
+        };
+
+        let modified = if is_url {
+            None
+        } else {
+            std::fs::metadata(&location)
+                .ok()
+                .and_then(|m| m.modified().ok())
+        };
+
+        let fresh = self
+            .preview_cache
+            .get(&location)
+            .is_some_and(|preview| preview.modified == modified);
+
+        if !fresh {
+            let content = if is_url {
+                self.github_fetcher.cached(&location)
+            } else {
+                std::fs::read_to_string(&location).ok()
+            };
+
+            match content {
+                Some(content) => {
+                    let lines = self.render_preview(&content);
+                    if self.preview_cache.len() >= PREVIEW_CACHE_SIZE {
+                        self.preview_cache.clear();
+                    }
+                    self.preview_cache
+                        .insert(location.clone(), Preview { modified, lines });
+                }
+                None => {
+                    self.preview_cache.remove(&location);
+                }
+            }
+        }
+
+        self.preview_location = Some(location);
+    }
+
+    /// Render the start of a document for a preview
+    fn render_preview(&self, content: &str) -> Vec<ratatui::text::Line<'static>> {
+        // Cut at a line break so large documents stay cheap to parse
+        let content = if content.len() > PREVIEW_MAX_BYTES {
+            let mut end = PREVIEW_MAX_BYTES;
+            while !content.is_char_boundary(end) {
+                end -= 1;
+            }
+            let end = content[..end].rfind('\n').unwrap_or(end);
+            &content[..end]
+        } else {
+            content
+        };
+
+        let highlighter = self
+            .highlighter
+            .as_ref()
+            .filter(|_| self.syntax_highlighting);
+        let mut document = Document::parse(content);
+        let mut lines = document.render_with_highlighting(&self.theme, highlighter);
+        lines.truncate(PREVIEW_MAX_LINES);
+        lines
+    }
+
+    /// Get the preview for the highlighted overlay entry, if available
+    pub fn current_preview(&self) -> Option<&Preview> {
+        self.preview_location
+            .as_ref()
+            .and_then(|location| self.preview_cache.get(location))
+    }
+
     // === History ===
 
     /// Open history overlay
@@ -1228,6 +3204,7 @@ impl AppState {
         }
         self.show_history = true;
         self.history_selected = 0;
+        self.update_preview();
     }
 
     /// Close history overlay
@@ -1239,6 +3216,7 @@ impl AppState {
     pub fn history_up(&mut self) {
         if self.history_selected > 0 {
             self.history_selected -= 1;
+            self.update_preview();
         }
     }
 
@@ -1247,6 +3225,7 @@ impl AppState {
         let max = self.history.entries().len().saturating_sub(1);
         if self.history_selected < max {
             self.history_selected += 1;
+            self.update_preview();
         }
     }
 
@@ -1278,6 +3257,7 @@ impl AppState {
         }
         self.show_bookmarks = true;
         self.bookmarks_selected = 0;
+        self.update_preview();
     }
 
     /// Close bookmarks overlay
@@ -1289,6 +3269,7 @@ impl AppState {
     pub fn bookmarks_up(&mut self) {
         if self.bookmarks_selected > 0 {
             self.bookmarks_selected -= 1;
+            self.update_preview();
         }
     }
 
@@ -1297,6 +3278,7 @@ impl AppState {
         let max = self.bookmarks.entries().len().saturating_sub(1);
         if self.bookmarks_selected < max {
             self.bookmarks_selected += 1;
+            self.update_preview();
         }
     }
 
@@ -1397,6 +3379,7 @@ impl AppState {
             if self.bookmarks.entries().is_empty() {
                 self.show_bookmarks = false;
             }
+            self.update_preview();
         }
     }
 
@@ -1405,3 +3388,11 @@ impl AppState {
         let _ = self.history.save();
     }
 }
+
+/// The first `count` lines of `text`
+fn first_lines(text: &str, count: usize) -> &str {
+    match text.match_indices('\n').nth(count.saturating_sub(1)) {
+        Some((end, _)) => &text[..=end],
+        None => text,
+    }
+}
@@ -0,0 +1,415 @@
+use crate::config::Config;
+use barkdocs::export::ExportFormat;
+use std::path::PathBuf;
+
+/// A file, directory or URL to open
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Target {
+    pub location: String,
+    /// Line to start at (1-based), from `+LINE` or `file.md:LINE`
+    pub line: Option<usize>,
+}
+
+impl Target {
+    /// Whether the target is a URL
+    pub fn is_url(&self) -> bool {
+        is_url(&self.location)
+    }
+}
+
+/// When `--print` output uses ANSI colors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Color when stdout is a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Non-interactive query run instead of the viewer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Subcommand {
+    /// `toc FILE`: print the heading tree with anchors
+    Toc,
+    /// `section FILE HEADING`: print one section
+    Section { heading: String },
+    /// `links FILE`: list links with line numbers
+    Links,
+}
+
+/// Parsed command-line arguments
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CliArgs {
+    pub targets: Vec<Target>,
+    pub help: bool,
+    pub version: bool,
+    pub theme: Option<String>,
+    pub show_outline: Option<bool>,
+    pub line_wrap: Option<bool>,
+    pub line_numbers: Option<bool>,
+    /// Search pattern to apply after opening
+    pub search: Option<String>,
+    /// Heading to open at
+    pub heading: Option<String>,
+    /// Alternate config file
+    pub config_path: Option<PathBuf>,
+    /// Keep re-rendering the file or stdin as it grows
+    pub follow: bool,
+    /// Render to stdout and exit instead of starting the viewer
+    pub print: bool,
+    /// Width to wrap `--print` output at
+    pub width: Option<u16>,
+    /// Whether `--print` output is colored
+    pub color: ColorMode,
+    /// Export to this format and exit instead of starting the viewer
+    pub export: Option<ExportFormat>,
+    /// File to write `--print`/`--export` output to (stdout by default)
+    pub output: Option<PathBuf>,
+    pub command: Option<Subcommand>,
+    /// Print the parsed document structure as JSON and exit
+    pub json: bool,
+}
+
+impl CliArgs {
+    /// Apply option overrides to a config (after environment overrides)
+    pub fn apply_to(&self, config: &mut Config) {
+        if let Some(theme) = &self.theme {
+            config.theme = theme.clone();
+        }
+        if let Some(show_outline) = self.show_outline {
+            config.show_outline = show_outline;
+        }
+        if let Some(line_wrap) = self.line_wrap {
+            config.line_wrap = line_wrap;
+        }
+        if let Some(line_numbers) = self.line_numbers {
+            config.show_line_numbers = line_numbers;
+        }
+    }
+}
+
+/// Check if an argument looks like a URL
+fn is_url(arg: &str) -> bool {
+    arg.starts_with("http://") || arg.starts_with("https://")
+}
+
+/// Parse command-line arguments (excluding the program name)
+pub fn parse_args<I>(args: I) -> Result<CliArgs, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut cli = CliArgs::default();
+    let mut args = args.into_iter();
+    // Line from `+LINE`, applied to the next target
+    let mut pending_line: Option<usize> = None;
+    let mut only_targets = false;
+
+    while let Some(arg) = args.next() {
+        if only_targets || !arg.starts_with('-') || arg == "-" {
+            if let Some(line) = arg.strip_prefix('+').filter(|_| !only_targets) {
+                pending_line = Some(parse_line(line)?);
+                continue;
+            }
+            // A subcommand must be the first positional argument
+            if cli.command.is_none() && cli.targets.is_empty() && !only_targets {
+                cli.command = match arg.as_str() {
+                    "toc" => Some(Subcommand::Toc),
+                    "section" => Some(Subcommand::Section {
+                        heading: String::new(),
+                    }),
+                    "links" => Some(Subcommand::Links),
+                    _ => None,
+                };
+                if cli.command.is_some() {
+                    continue;
+                }
+            }
+            // The section heading is taken as-is (not as a `file:LINE` target)
+            if let Some(Subcommand::Section { heading }) = &mut cli.command
+                && heading.is_empty()
+                && cli.targets.len() == 1
+            {
+                *heading = arg;
+                continue;
+            }
+            let mut target = parse_target(&arg);
+            if target.line.is_none() {
+                target.line = pending_line.take();
+            }
+            cli.targets.push(target);
+            continue;
+        }
+
+        // Support both `--flag value` and `--flag=value`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            match inline_value {
+                Some(value) => Ok(value.to_string()),
+                None => args
+                    .next()
+                    .ok_or_else(|| format!("{} requires a value", name)),
+            }
+        };
+
+        match flag.as_str() {
+            "--" => only_targets = true,
+            "-h" | "--help" => cli.help = true,
+            "-V" | "--version" => cli.version = true,
+            "--theme" => cli.theme = Some(value("--theme")?),
+            "--outline" => cli.show_outline = Some(true),
+            "--no-outline" => cli.show_outline = Some(false),
+            "--wrap" => cli.line_wrap = Some(true),
+            "--no-wrap" => cli.line_wrap = Some(false),
+            "--line-numbers" => cli.line_numbers = Some(true),
+            "--no-line-numbers" => cli.line_numbers = Some(false),
+            "--search" => cli.search = Some(value("--search")?),
+            "--heading" => cli.heading = Some(value("--heading")?),
+            "--config" => cli.config_path = Some(PathBuf::from(value("--config")?)),
+            "--follow" => cli.follow = true,
+            "-p" | "--print" => cli.print = true,
+            "--width" => {
+                let width = value("--width")?;
+                cli.width = match width.parse::<u16>() {
+                    Ok(width) if width > 0 => Some(width),
+                    _ => return Err(format!("invalid width: {}", width)),
+                };
+            }
+            "--color" => {
+                cli.color = match value("--color")?.as_str() {
+                    "auto" => ColorMode::Auto,
+                    "always" => ColorMode::Always,
+                    "never" => ColorMode::Never,
+                    other => return Err(format!("invalid color mode: {}", other)),
+                };
+            }
+            "--export" => {
+                let format = value("--export")?;
+                cli.export = Some(
+                    ExportFormat::from_name(&format)
+                        .ok_or_else(|| format!("invalid export format: {}", format))?,
+                );
+            }
+            "--json" => cli.json = true,
+            "-o" | "--output" => cli.output = Some(PathBuf::from(value("--output")?)),
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+
+    match &cli.command {
+        Some(Subcommand::Section { heading }) if heading.is_empty() => {
+            return Err("section requires a FILE and a HEADING".to_string());
+        }
+        Some(_) if cli.targets.len() > 1 => {
+            return Err("subcommands take a single FILE".to_string());
+        }
+        _ => {}
+    }
+    if cli.json && cli.targets.len() > 1 {
+        return Err("--json takes a single FILE".to_string());
+    }
+
+    // A trailing `+LINE` applies to the last target
+    if let Some(line) = pending_line {
+        match cli.targets.last_mut() {
+            Some(target) => target.line = Some(line),
+            None => return Err("+LINE needs a file to open".to_string()),
+        }
+    }
+
+    Ok(cli)
+}
+
+/// Parse a 1-based line number
+fn parse_line(text: &str) -> Result<usize, String> {
+    match text.parse::<usize>() {
+        Ok(line) if line > 0 => Ok(line),
+        _ => Err(format!("invalid line number: {}", text)),
+    }
+}
+
+/// Split a `file.md:LINE` suffix off a local path
+fn parse_target(arg: &str) -> Target {
+    if !is_url(arg)
+        && let Some((path, line)) = arg.rsplit_once(':')
+        && !path.is_empty()
+        && let Ok(line) = parse_line(line)
+    {
+        return Target {
+            location: path.to_string(),
+            line: Some(line),
+        };
+    }
+
+    Target {
+        location: arg.to_string(),
+        line: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    fn target(location: &str, line: Option<usize>) -> Target {
+        Target {
+            location: location.to_string(),
+            line,
+        }
+    }
+
+    #[test]
+    fn test_no_args() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+    }
+
+    #[test]
+    fn test_targets_and_flags() {
+        let cli = parse(&["--no-outline", "a.md", "--wrap", "b.md", "--line-numbers"]).unwrap();
+        assert_eq!(
+            cli.targets,
+            vec![target("a.md", None), target("b.md", None)]
+        );
+        assert_eq!(cli.show_outline, Some(false));
+        assert_eq!(cli.line_wrap, Some(true));
+        assert_eq!(cli.line_numbers, Some(true));
+    }
+
+    #[test]
+    fn test_values_separate_and_inline() {
+        let cli = parse(&["--theme", "nord", "--search=TODO", "--heading", "Install"]).unwrap();
+        assert_eq!(cli.theme.as_deref(), Some("nord"));
+        assert_eq!(cli.search.as_deref(), Some("TODO"));
+        assert_eq!(cli.heading.as_deref(), Some("Install"));
+
+        let cli = parse(&["--config", "/tmp/alt.toml", "--follow", "-"]).unwrap();
+        assert_eq!(cli.config_path, Some(PathBuf::from("/tmp/alt.toml")));
+        assert!(cli.follow);
+        assert_eq!(cli.targets, vec![target("-", None)]);
+    }
+
+    #[test]
+    fn test_print_options() {
+        let cli = parse(&["-p", "--width=72", "--color", "never", "a.md"]).unwrap();
+        assert!(cli.print);
+        assert_eq!(cli.width, Some(72));
+        assert_eq!(cli.color, ColorMode::Never);
+        assert!(parse(&["--width", "0"]).is_err());
+        assert!(parse(&["--color", "sometimes"]).is_err());
+    }
+
+    #[test]
+    fn test_export_options() {
+        let cli = parse(&["--export", "html", "-o", "out.html", "a.md"]).unwrap();
+        assert_eq!(cli.export, Some(ExportFormat::Html));
+        assert_eq!(cli.output, Some(PathBuf::from("out.html")));
+        assert_eq!(cli.targets, vec![target("a.md", None)]);
+        assert!(parse(&["--export=pdf"]).is_err());
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let cli = parse(&["toc", "README.md"]).unwrap();
+        assert_eq!(cli.command, Some(Subcommand::Toc));
+        assert_eq!(cli.targets, vec![target("README.md", None)]);
+
+        let cli = parse(&["section", "README.md", "Step:2", "--print"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(Subcommand::Section {
+                heading: "Step:2".to_string()
+            })
+        );
+        assert_eq!(cli.targets, vec![target("README.md", None)]);
+        assert!(cli.print);
+
+        assert_eq!(parse(&["links"]).unwrap().command, Some(Subcommand::Links));
+        assert!(parse(&["section", "README.md"]).is_err());
+        assert!(parse(&["toc", "a.md", "b.md"]).is_err());
+
+        // Only the first positional is a subcommand
+        let cli = parse(&["a.md", "toc"]).unwrap();
+        assert_eq!(cli.command, None);
+        assert_eq!(cli.targets, vec![target("a.md", None), target("toc", None)]);
+    }
+
+    #[test]
+    fn test_json_takes_single_file() {
+        let cli = parse(&["--json", "a.md"]).unwrap();
+        assert!(cli.json);
+        assert!(parse(&["--json", "a.md", "b.md"]).is_err());
+    }
+
+    #[test]
+    fn test_missing_value_is_error() {
+        assert!(parse(&["--theme"]).is_err());
+    }
+
+    #[test]
+    fn test_unknown_option_is_error() {
+        assert_eq!(
+            parse(&["--bogus"]).unwrap_err(),
+            "unknown option: --bogus".to_string()
+        );
+    }
+
+    #[test]
+    fn test_plus_line_applies_to_next_target() {
+        let cli = parse(&["+42", "a.md", "b.md"]).unwrap();
+        assert_eq!(
+            cli.targets,
+            vec![target("a.md", Some(42)), target("b.md", None)]
+        );
+    }
+
+    #[test]
+    fn test_trailing_plus_line_applies_to_last_target() {
+        let cli = parse(&["a.md", "+7"]).unwrap();
+        assert_eq!(cli.targets, vec![target("a.md", Some(7))]);
+        assert!(parse(&["+7"]).is_err());
+        assert!(parse(&["+x", "a.md"]).is_err());
+    }
+
+    #[test]
+    fn test_file_colon_line() {
+        let cli = parse(&["docs/guide.md:120", "notes.md:abc"]).unwrap();
+        assert_eq!(
+            cli.targets,
+            vec![
+                target("docs/guide.md", Some(120)),
+                target("notes.md:abc", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_urls_keep_port_colons() {
+        let cli = parse(&["http://localhost:8080"]).unwrap();
+        assert_eq!(cli.targets, vec![target("http://localhost:8080", None)]);
+        assert!(cli.targets[0].is_url());
+    }
+
+    #[test]
+    fn test_double_dash_ends_options() {
+        let cli = parse(&["--", "--weird.md"]).unwrap();
+        assert_eq!(cli.targets, vec![target("--weird.md", None)]);
+    }
+
+    #[test]
+    fn test_apply_to_config() {
+        let cli = parse(&["--theme", "nord", "--no-wrap", "--no-outline"]).unwrap();
+        let mut config = Config::default();
+        cli.apply_to(&mut config);
+        assert_eq!(config.theme, "nord");
+        assert!(!config.line_wrap);
+        assert!(!config.show_outline);
+        assert!(!config.show_line_numbers);
+    }
+}
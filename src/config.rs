@@ -1,6 +1,6 @@
-use crate::theme::Theme;
+use barkdocs::theme::Theme;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Configuration for barkdocs
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -18,8 +18,13 @@ pub struct Config {
     pub show_line_numbers: bool,
     /// Whether to enable syntax highlighting
     pub syntax_highlighting: bool,
+    /// Syntax highlighting theme for code blocks, overriding the one that
+    /// goes with `theme`
+    pub syntax_theme: Option<String>,
     /// Whether to enable auto-reload on file changes
     pub auto_reload: bool,
+    /// Whether a reload scrolls to the first changed line
+    pub jump_to_change: bool,
 }
 
 impl Default for Config {
@@ -31,7 +36,9 @@ impl Default for Config {
             outline_width: 24,
             show_line_numbers: false,
             syntax_highlighting: true,
+            syntax_theme: None,
             auto_reload: true,
+            jump_to_change: false,
         }
     }
 }
@@ -49,6 +56,17 @@ impl Config {
         config
     }
 
+    /// Load config from an alternate file, applying environment variable overrides.
+    /// Unlike the default config file, this one must exist and parse.
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut config: Self = toml::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        config.apply_env_overrides();
+        Ok(config)
+    }
+
     /// Load config from file only
     fn load_from_file() -> Option<Self> {
         let path = Self::config_path()?;
@@ -84,15 +102,27 @@ impl Config {
                 matches!(syntax.to_lowercase().as_str(), "1" | "true" | "yes");
         }
 
+        if let Ok(syntax_theme) = std::env::var("BARKDOCS_SYNTAX_THEME") {
+            self.syntax_theme = Some(syntax_theme).filter(|name| !name.is_empty());
+        }
+
         if let Ok(reload) = std::env::var("BARKDOCS_AUTO_RELOAD") {
             self.auto_reload = matches!(reload.to_lowercase().as_str(), "1" | "true" | "yes");
         }
+
+        if let Ok(jump) = std::env::var("BARKDOCS_JUMP_TO_CHANGE") {
+            self.jump_to_change = matches!(jump.to_lowercase().as_str(), "1" | "true" | "yes");
+        }
     }
 
-    /// Save config to file
+    /// Save config to the default config file
     pub fn save(&self) -> Result<(), String> {
         let path = Self::config_path().ok_or("Could not determine config path")?;
+        self.save_to(&path)
+    }
 
+    /// Save config to a specific file
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
         // Create parent directories if needed
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)
@@ -102,11 +132,19 @@ impl Config {
         let contents = toml::to_string_pretty(self)
             .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-        std::fs::write(&path, contents).map_err(|e| format!("Failed to write config: {}", e))
+        std::fs::write(path, contents).map_err(|e| format!("Failed to write config: {}", e))
     }
 
     /// Get the theme based on config
     pub fn get_theme(&self) -> Theme {
         Theme::by_name(&self.theme)
     }
+
+    /// Get the syntax highlighting theme: the override, or the one paired
+    /// with the theme
+    pub fn get_syntax_theme(&self) -> String {
+        self.syntax_theme
+            .clone()
+            .unwrap_or_else(|| self.get_theme().syntax_theme.to_string())
+    }
 }
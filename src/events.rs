@@ -0,0 +1,122 @@
+use crossterm::event::Event;
+use std::sync::mpsc::{self, Receiver, SendError, Sender};
+use std::sync::{Arc, OnceLock};
+
+/// Something the main loop has to react to
+#[derive(Debug)]
+pub enum AppEvent {
+    /// Key, mouse or resize event from the terminal
+    Terminal(Event),
+    /// A background worker has results waiting in its channel
+    Worker(Worker),
+}
+
+/// Background workers that wake the main loop
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Worker {
+    FileWatch,
+    Follow,
+    Fetch,
+    ProjectSearch,
+    Render,
+    Highlighter,
+}
+
+/// Handle for waking the main loop from background threads.
+///
+/// Wake-ups are dropped until the loop connects its channel, so workers
+/// started during setup (or without a loop, as in `--print`) are unaffected.
+#[derive(Clone, Debug, Default)]
+pub struct Waker(Arc<OnceLock<Sender<AppEvent>>>);
+
+impl Waker {
+    /// Deliver wake-ups to the main loop's channel
+    pub fn connect(&self, tx: Sender<AppEvent>) {
+        let _ = self.0.set(tx);
+    }
+
+    pub fn wake(&self, worker: Worker) {
+        if let Some(tx) = self.0.get() {
+            let _ = tx.send(AppEvent::Worker(worker));
+        }
+    }
+
+    /// Channel for a worker whose sends wake the main loop
+    pub fn channel<T>(&self, worker: Worker) -> (WakingSender<T>, Receiver<T>) {
+        let (tx, rx) = mpsc::channel();
+        let tx = WakingSender {
+            tx: Some(tx),
+            waker: self.clone(),
+            worker,
+        };
+        (tx, rx)
+    }
+}
+
+/// Sending half of a worker channel. Wakes the main loop after each message,
+/// and once more when dropped so the loop notices the disconnect.
+pub struct WakingSender<T> {
+    tx: Option<Sender<T>>,
+    waker: Waker,
+    worker: Worker,
+}
+
+impl<T> WakingSender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let result = match &self.tx {
+            Some(tx) => tx.send(value),
+            None => Err(SendError(value)),
+        };
+        self.waker.wake(self.worker);
+        result
+    }
+}
+
+impl<T> Drop for WakingSender<T> {
+    fn drop(&mut self) {
+        // Disconnect before waking, so the woken loop sees it
+        drop(self.tx.take());
+        self.waker.wake(self.worker);
+    }
+}
+
+/// Read terminal events on a background thread until the channel closes
+pub fn spawn_terminal_reader(tx: Sender<AppEvent>) {
+    std::thread::spawn(move || {
+        while let Ok(event) = crossterm::event::read() {
+            if tx.send(AppEvent::Terminal(event)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waking_sender_wakes_on_send_and_drop() {
+        let (events_tx, events) = mpsc::channel();
+        let waker = Waker::default();
+        let (early_tx, _early_rx) = waker.channel::<u32>(Worker::Fetch);
+        early_tx.send(0).unwrap();
+        assert!(events.try_recv().is_err());
+
+        waker.connect(events_tx);
+        let (tx, rx) = waker.channel(Worker::Follow);
+        tx.send(1).unwrap();
+        assert!(matches!(
+            events.try_recv(),
+            Ok(AppEvent::Worker(Worker::Follow))
+        ));
+        assert_eq!(rx.try_recv(), Ok(1));
+
+        drop(tx);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(AppEvent::Worker(Worker::Follow))
+        ));
+        assert_eq!(rx.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+    }
+}
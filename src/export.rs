@@ -0,0 +1,474 @@
+use crate::markdown::Document;
+use crate::theme::Theme;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Widget, Wrap};
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+/// Width plain and ANSI exports wrap at when no other width applies
+pub const DEFAULT_WIDTH: u16 = 80;
+
+/// Output format for exported documents
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Standalone HTML page styled with the theme's colors
+    Html,
+    /// Plain wrapped text
+    Text,
+    /// Wrapped text with ANSI escape sequences
+    Ansi,
+}
+
+impl ExportFormat {
+    /// Parse a format name (`html`, `text`/`txt` or `ansi`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "html" | "htm" => Some(Self::Html),
+            "text" | "txt" => Some(Self::Text),
+            "ansi" => Some(Self::Ansi),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from a file extension, defaulting to plain text
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|ext| Self::from_name(&ext.to_string_lossy()))
+            .unwrap_or(Self::Text)
+    }
+
+    /// File extension for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Text => "txt",
+            Self::Ansi => "ansi",
+        }
+    }
+}
+
+/// Export a rendered document in the given format
+pub fn export(
+    format: ExportFormat,
+    document: &Document,
+    lines: &[Line<'static>],
+    theme: &Theme,
+    title: &str,
+    width: u16,
+) -> String {
+    match format {
+        ExportFormat::Html => html_page(title, &[html_body(document, lines)], theme),
+        ExportFormat::Text => to_ansi(lines, width, false),
+        ExportFormat::Ansi => to_ansi(lines, width, true),
+    }
+}
+
+/// Wrap a rendered line to a width the same way the viewer does, returning
+/// the laid-out cells
+pub fn layout(line: &Line<'static>, width: u16) -> Buffer {
+    let width = width.max(1);
+    let paragraph = Paragraph::new(line.clone()).wrap(Wrap { trim: false });
+    let height = paragraph.line_count(width).min(u16::MAX as usize) as u16;
+    let area = Rect::new(0, 0, width, height);
+    let mut buffer = Buffer::empty(area);
+    paragraph.render(area, &mut buffer);
+    buffer
+}
+
+/// Render lines as text wrapped to `width`, with ANSI styles when `color` is set
+pub fn to_ansi(lines: &[Line<'static>], width: u16, color: bool) -> String {
+    let mut output = String::new();
+    // One line at a time, so long documents aren't limited to a buffer's
+    // u16 height and are never laid out in one piece
+    for line in lines {
+        push_rows(&mut output, &layout(line, width), color);
+    }
+    output
+}
+
+/// Append each row of laid-out cells to `output`
+fn push_rows(output: &mut String, buffer: &Buffer, color: bool) {
+    let area = buffer.area;
+
+    for y in 0..area.height {
+        // Trailing cells that would print as nothing are dropped
+        let mut end = area.width;
+        while end > 0 {
+            let cell = &buffer[(end - 1, y)];
+            if cell.symbol().trim().is_empty() && (!color || cell.bg == Color::Reset) {
+                end -= 1;
+            } else {
+                break;
+            }
+        }
+
+        let reset = sgr(Style::default());
+        let mut current = reset.clone();
+        let mut x = 0;
+        while x < end {
+            let cell = &buffer[(x, y)];
+            if color {
+                let style = sgr(cell.style());
+                if style != current {
+                    output.push_str(&style);
+                    current = style;
+                }
+            }
+            let symbol = cell.symbol();
+            output.push_str(symbol);
+            // Skip the cells hidden behind a wide character
+            x += symbol.width().max(1) as u16;
+        }
+
+        if color && current != reset {
+            output.push_str(&reset);
+        }
+        output.push('\n');
+    }
+}
+
+/// Render lines as HTML, one `<span>` per styled span, with links made clickable.
+/// The result goes inside the `<pre>` built by `html_page`.
+pub fn html_body(document: &Document, lines: &[Line<'static>]) -> String {
+    let mut body = String::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        // Runs of (link, css, text), merging neighbours that look the same
+        let mut runs: Vec<(Option<&str>, String, String)> = Vec::new();
+        let mut column = 0;
+        for span in &line.spans {
+            let link = document
+                .rendered_links
+                .iter()
+                .find(|link| link.line == index && column >= link.start && column < link.end)
+                .map(|link| link.url.as_str());
+            let css = css_style(span.style);
+            match runs.last_mut() {
+                Some((last_link, last_css, text)) if *last_link == link && *last_css == css => {
+                    text.push_str(&span.content);
+                }
+                _ => runs.push((link, css, span.content.to_string())),
+            }
+            column += span.width();
+        }
+
+        for (link, css, text) in runs {
+            let text = escape_html(&text);
+            let styled = if css.is_empty() {
+                text
+            } else {
+                format!("<span style=\"{}\">{}</span>", css, text)
+            };
+            match link {
+                Some(url) => {
+                    body.push_str(&format!("<a href=\"{}\">{}</a>", escape_html(url), styled))
+                }
+                None => body.push_str(&styled),
+            }
+        }
+        body.push('\n');
+    }
+
+    body
+}
+
+/// Wrap rendered bodies in a standalone page whose colors come from the theme
+pub fn html_page(title: &str, bodies: &[String], theme: &Theme) -> String {
+    let background = css_color(theme.header_bg).unwrap_or_else(|| "#000000".to_string());
+    let text = css_color(theme.text).unwrap_or_else(|| "#ffffff".to_string());
+    let rule = css_color(theme.horizontal_rule).unwrap_or_else(|| text.clone());
+
+    let mut page = format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n\
+         <style>\n\
+         body {{ background: {background}; color: {text}; margin: 2em; }}\n\
+         pre {{ font-family: ui-monospace, Menlo, Consolas, monospace; white-space: pre-wrap; }}\n\
+         a {{ color: inherit; }}\n\
+         hr {{ border: none; border-top: 1px solid {rule}; }}\n\
+         </style>\n\
+         </head>\n\
+         <body>\n",
+        title = escape_html(title),
+    );
+
+    for (i, body) in bodies.iter().enumerate() {
+        if i > 0 {
+            page.push_str("<hr>\n");
+        }
+        page.push_str("<pre>");
+        page.push_str(body);
+        page.push_str("</pre>\n");
+    }
+
+    page.push_str("</body>\n</html>\n");
+    page
+}
+
+/// Inline CSS for a span style (empty when unstyled)
+fn css_style(style: Style) -> String {
+    let mut css = Vec::new();
+
+    if let Some(fg) = style.fg.and_then(css_color) {
+        css.push(format!("color: {}", fg));
+    }
+    if let Some(bg) = style.bg.and_then(css_color) {
+        css.push(format!("background: {}", bg));
+    }
+    if style.add_modifier.contains(Modifier::BOLD) {
+        css.push("font-weight: bold".to_string());
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        css.push("font-style: italic".to_string());
+    }
+
+    let decorations: Vec<&str> = [
+        (Modifier::UNDERLINED, "underline"),
+        (Modifier::CROSSED_OUT, "line-through"),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| style.add_modifier.contains(*modifier))
+    .map(|(_, decoration)| decoration)
+    .collect();
+    if !decorations.is_empty() {
+        css.push(format!("text-decoration: {}", decorations.join(" ")));
+    }
+
+    css.join("; ")
+}
+
+/// CSS hex color for a terminal color, using the xterm palette for named
+/// and indexed colors
+fn css_color(color: Color) -> Option<String> {
+    const ANSI: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+
+    let (r, g, b) = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(i) if i < 16 => ANSI[i as usize],
+        Color::Indexed(i) if i < 232 => {
+            // 6x6x6 color cube
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = i - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        Color::Indexed(i) => {
+            let gray = 8 + (i - 232) * 10;
+            (gray, gray, gray)
+        }
+        Color::Black => ANSI[0],
+        Color::Red => ANSI[1],
+        Color::Green => ANSI[2],
+        Color::Yellow => ANSI[3],
+        Color::Blue => ANSI[4],
+        Color::Magenta => ANSI[5],
+        Color::Cyan => ANSI[6],
+        Color::Gray => ANSI[7],
+        Color::DarkGray => ANSI[8],
+        Color::LightRed => ANSI[9],
+        Color::LightGreen => ANSI[10],
+        Color::LightYellow => ANSI[11],
+        Color::LightBlue => ANSI[12],
+        Color::LightMagenta => ANSI[13],
+        Color::LightCyan => ANSI[14],
+        Color::White => ANSI[15],
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+/// Escape text for HTML content and attribute values
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Build the escape sequence that switches to `style` from a reset state
+fn sgr(style: Style) -> String {
+    let mut codes = vec!["0".to_string()];
+
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in modifiers {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+
+    if let Some(fg) = style.fg.and_then(|c| color_code(c, false)) {
+        codes.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|c| color_code(c, true)) {
+        codes.push(bg);
+    }
+
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// SGR parameters for a foreground or background color
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+        Color::Indexed(i) => return Some(format!("{};5;{}", base + 8, i)),
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+    };
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::text::Span;
+
+    #[test]
+    fn test_plain_output_wraps_and_trims() {
+        let lines = vec![Line::from("hello world again"), Line::from("")];
+        assert_eq!(to_ansi(&lines, 11, false), "hello world\nagain\n\n");
+    }
+
+    #[test]
+    fn test_long_documents_are_not_truncated() {
+        let lines = vec![Line::from(["word"; 6].join(" ")); 33_000];
+        let output = to_ansi(&lines, 20, false);
+        // Each line wraps to two rows, past what a single buffer can hold
+        assert_eq!(output.lines().count(), 66_000);
+        assert!(output.ends_with("word word word word\nword word\n"));
+    }
+
+    #[test]
+    fn test_ansi_output_styles_spans() {
+        let lines = vec![Line::from(vec![
+            Span::raw("a "),
+            Span::styled(
+                "b",
+                Style::default()
+                    .fg(Color::Rgb(1, 2, 3))
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ])];
+        assert_eq!(
+            to_ansi(&lines, 10, true),
+            "a \x1b[0;1;38;2;1;2;3mb\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_wide_characters_are_not_padded() {
+        let lines = vec![Line::from("日本")];
+        assert_eq!(to_ansi(&lines, 10, false), "日本\n");
+    }
+
+    #[test]
+    fn test_named_colors() {
+        assert_eq!(color_code(Color::Red, false), Some("31".to_string()));
+        assert_eq!(color_code(Color::White, true), Some("107".to_string()));
+        assert_eq!(
+            color_code(Color::Indexed(42), true),
+            Some("48;5;42".to_string())
+        );
+        assert_eq!(color_code(Color::Reset, false), None);
+    }
+
+    #[test]
+    fn test_format_names_and_paths() {
+        assert_eq!(ExportFormat::from_name("HTML"), Some(ExportFormat::Html));
+        assert_eq!(ExportFormat::from_name("txt"), Some(ExportFormat::Text));
+        assert_eq!(ExportFormat::from_name("pdf"), None);
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out/readme.htm")),
+            ExportFormat::Html
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("notes")),
+            ExportFormat::Text
+        );
+    }
+
+    #[test]
+    fn test_html_body_styles_and_links() {
+        let mut document = Document::parse("Hi **x &lt; y** [docs](https://x.dev/?a=1&b=2)\n");
+        let lines = document.render(&Theme::default());
+        let body = html_body(&document, &lines);
+        assert!(body.contains("font-weight: bold\">x &lt; y</span>"));
+        assert!(body.contains("<a href=\"https://x.dev/?a=1&amp;b=2\"><span"));
+        assert!(body.contains("text-decoration: underline\">docs</span></a>"));
+    }
+
+    #[test]
+    fn test_html_page_uses_theme_colors() {
+        let theme = Theme::default();
+        let page = html_page("a <b>", &["x".to_string()], &theme);
+        assert!(page.contains("<title>a &lt;b&gt;</title>"));
+        assert!(page.contains(&format!(
+            "background: {};",
+            css_color(theme.header_bg).unwrap()
+        )));
+        assert!(page.contains("<pre>x</pre>"));
+    }
+
+    #[test]
+    fn test_css_colors() {
+        assert_eq!(
+            css_color(Color::Rgb(255, 0, 16)),
+            Some("#ff0010".to_string())
+        );
+        assert_eq!(css_color(Color::Indexed(196)), Some("#ff0000".to_string()));
+        assert_eq!(css_color(Color::Indexed(232)), Some("#080808".to_string()));
+        assert_eq!(css_color(Color::White), Some("#ffffff".to_string()));
+        assert_eq!(css_color(Color::Reset), None);
+    }
+}
@@ -0,0 +1,453 @@
+use ignore::WalkBuilder;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// File extensions recognized as markdown
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdx", "mkd"];
+
+/// Maximum number of files collected when scanning a tree
+const MAX_SCAN_FILES: usize = 10_000;
+
+/// Check if a path looks like a markdown file
+/// (known extension, or a `README` without extension)
+pub fn is_markdown_file(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => MARKDOWN_EXTENSIONS
+            .iter()
+            .any(|known| ext.eq_ignore_ascii_case(known)),
+        None => path
+            .file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case("readme")),
+    }
+}
+
+/// Check if a path is a README file
+pub fn is_readme(path: &Path) -> bool {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_lowercase().starts_with("readme"))
+        .unwrap_or(false)
+}
+
+/// Walk a directory tree for markdown files.
+///
+/// Respects `.gitignore` (even outside a git repository) and skips hidden
+/// files and directories.
+pub fn walk_markdown_files(root: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkBuilder::new(root)
+        .hidden(true)
+        .git_ignore(true)
+        .require_git(false)
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| is_markdown_file(path))
+}
+
+/// Collect markdown files under `root`, README files first, then alphabetical
+pub fn scan_markdown_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = walk_markdown_files(root).take(MAX_SCAN_FILES).collect();
+    files.sort_by(|a, b| match (is_readme(a), is_readme(b)) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.cmp(b),
+    });
+    files
+}
+
+/// List the immediate subdirectories of `dir` (skipping hidden and ignored ones)
+pub fn list_subdirectories(dir: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(dir)
+        .hidden(true)
+        .git_ignore(true)
+        .require_git(false)
+        .max_depth(Some(1))
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.depth() == 1 && entry.file_type().is_some_and(|t| t.is_dir()))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// An entry in a file tree (directory or markdown file)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeEntry {
+    pub path: PathBuf,
+    pub name: String,
+    /// Nesting level, 0 for entries directly under the root
+    pub depth: usize,
+    pub is_dir: bool,
+}
+
+/// Expandable tree of the markdown files under a directory.
+///
+/// Only directories that contain markdown files (at any depth) are listed.
+/// Directories start collapsed.
+#[derive(Clone, Debug, Default)]
+pub struct FileTree {
+    pub root: PathBuf,
+    /// All entries in display order (directories first, then files)
+    entries: Vec<TreeEntry>,
+    expanded: HashSet<PathBuf>,
+}
+
+/// Intermediate directory node used while building a tree
+#[derive(Default)]
+struct DirNode {
+    dirs: BTreeMap<String, DirNode>,
+    files: Vec<String>,
+}
+
+impl FileTree {
+    /// Scan `root` for markdown files and build the tree
+    pub fn scan(root: &Path) -> Self {
+        let files: Vec<PathBuf> = walk_markdown_files(root).take(MAX_SCAN_FILES).collect();
+        Self::from_files(root, &files)
+    }
+
+    /// Build a tree from file paths under `root`
+    pub fn from_files(root: &Path, files: &[PathBuf]) -> Self {
+        let mut top = DirNode::default();
+
+        for file in files {
+            let Ok(relative) = file.strip_prefix(root) else {
+                continue;
+            };
+            let mut components: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            let Some(name) = components.pop() else {
+                continue;
+            };
+
+            let mut node = &mut top;
+            for dir in components {
+                node = node.dirs.entry(dir).or_default();
+            }
+            node.files.push(name);
+        }
+
+        let mut entries = Vec::new();
+        flatten(&top, root, 0, &mut entries);
+
+        Self {
+            root: root.to_path_buf(),
+            entries,
+            expanded: HashSet::new(),
+        }
+    }
+
+    /// Whether the tree has no files
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether a directory is expanded
+    pub fn is_expanded(&self, path: &Path) -> bool {
+        self.expanded.contains(path)
+    }
+
+    /// Entries not hidden inside a collapsed directory
+    pub fn visible(&self) -> Vec<&TreeEntry> {
+        let mut visible = Vec::new();
+        // Depth of the collapsed directory whose contents are being skipped
+        let mut collapsed_at: Option<usize> = None;
+
+        for entry in &self.entries {
+            if let Some(depth) = collapsed_at {
+                if entry.depth > depth {
+                    continue;
+                }
+                collapsed_at = None;
+            }
+            visible.push(entry);
+            if entry.is_dir && !self.expanded.contains(&entry.path) {
+                collapsed_at = Some(entry.depth);
+            }
+        }
+
+        visible
+    }
+
+    /// Expand or collapse a directory
+    pub fn toggle(&mut self, path: &Path) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.to_path_buf());
+        }
+    }
+
+    /// Expand a directory
+    pub fn expand(&mut self, path: &Path) {
+        self.expanded.insert(path.to_path_buf());
+    }
+
+    /// Collapse a directory
+    pub fn collapse(&mut self, path: &Path) {
+        self.expanded.remove(path);
+    }
+
+    /// Expand every directory above `path` so it becomes visible
+    pub fn reveal(&mut self, path: &Path) {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return;
+        };
+        let mut dir = self.root.clone();
+        let mut components: Vec<_> = relative.components().collect();
+        components.pop();
+        for component in components {
+            dir.push(component);
+            self.expanded.insert(dir.clone());
+        }
+    }
+}
+
+/// Append a directory node's entries in display order
+fn flatten(node: &DirNode, dir: &Path, depth: usize, entries: &mut Vec<TreeEntry>) {
+    for (name, child) in &node.dirs {
+        let path = dir.join(name);
+        entries.push(TreeEntry {
+            path: path.clone(),
+            name: name.clone(),
+            depth,
+            is_dir: true,
+        });
+        flatten(child, &path, depth + 1, entries);
+    }
+
+    let mut files: Vec<&String> = node.files.iter().collect();
+    files.sort_by(
+        |a, b| match (is_readme(Path::new(a)), is_readme(Path::new(b))) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => a.cmp(b),
+        },
+    );
+    for name in files {
+        entries.push(TreeEntry {
+            path: dir.join(name),
+            name: name.clone(),
+            depth,
+            is_dir: false,
+        });
+    }
+}
+
+/// Fuzzy-match `pattern` against `candidate` as a subsequence.
+///
+/// Returns a score (higher is better) and the char indices of matched
+/// characters, or None if not all pattern characters were found.
+/// Uses smart-case like search: case-sensitive only if the pattern has uppercase.
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    if pattern.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let case_sensitive = pattern.chars().any(char::is_uppercase);
+    let chars: Vec<char> = candidate.chars().collect();
+    let basename_start = chars.iter().rposition(|&c| c == '/').map_or(0, |i| i + 1);
+
+    let mut positions: Vec<usize> = Vec::new();
+    let mut score: i64 = 0;
+    let mut next = 0;
+
+    for pc in pattern.chars() {
+        let found = (next..chars.len()).find(|&i| {
+            if case_sensitive {
+                chars[i] == pc
+            } else {
+                chars[i].to_lowercase().eq(pc.to_lowercase())
+            }
+        })?;
+
+        score += 1;
+
+        // Consecutive matches and matches at word boundaries score higher
+        if let Some(&prev) = positions.last() {
+            if found == prev + 1 {
+                score += 5;
+            } else {
+                score -= (found - prev - 1).min(10) as i64;
+            }
+        }
+        let at_boundary = found == 0
+            || matches!(chars[found - 1], '/' | '_' | '-' | '.' | ' ')
+            || (chars[found].is_uppercase() && chars[found - 1].is_lowercase());
+        if at_boundary {
+            score += 8;
+        }
+
+        positions.push(found);
+        next = found + 1;
+    }
+
+    // Prefer matches in the file name over the directory part
+    if positions[0] >= basename_start {
+        score += 10;
+    }
+
+    // Prefer shorter candidates
+    score -= (chars.len() / 10) as i64;
+
+    Some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_is_markdown_file() {
+        assert!(is_markdown_file(Path::new("README.md")));
+        assert!(is_markdown_file(Path::new("docs/guide.MD")));
+        assert!(!is_markdown_file(Path::new("main.rs")));
+        assert!(!is_markdown_file(Path::new("Makefile")));
+    }
+
+    #[test]
+    fn test_is_markdown_file_other_extensions() {
+        assert!(is_markdown_file(Path::new("guide.markdown")));
+        assert!(is_markdown_file(Path::new("page.mdx")));
+        assert!(is_markdown_file(Path::new("notes.mkd")));
+        assert!(is_markdown_file(Path::new("README")));
+        assert!(is_markdown_file(Path::new("sub/Readme")));
+        assert!(!is_markdown_file(Path::new("README.txt")));
+        assert!(!is_markdown_file(Path::new("LICENSE")));
+    }
+
+    #[test]
+    fn test_fuzzy_match_subsequence() {
+        let (_, positions) = fuzzy_match("gde", "guide.md").unwrap();
+        assert_eq!(positions, vec![0, 3, 4]);
+        assert!(fuzzy_match("xyz", "guide.md").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_empty_pattern() {
+        assert_eq!(fuzzy_match("", "anything"), Some((0, Vec::new())));
+    }
+
+    #[test]
+    fn test_fuzzy_match_smart_case() {
+        assert!(fuzzy_match("readme", "README.md").is_some());
+        assert!(fuzzy_match("README", "readme.md").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_prefers_consecutive_and_basename() {
+        let (contiguous, _) = fuzzy_match("guide", "docs/guide.md").unwrap();
+        let (scattered, _) = fuzzy_match("guide", "go/ui/dev/e.md").unwrap();
+        assert!(contiguous > scattered);
+
+        let (in_name, _) = fuzzy_match("api", "docs/api.md").unwrap();
+        let (in_dir, _) = fuzzy_match("api", "api/overview.md").unwrap();
+        assert!(in_name > in_dir);
+    }
+
+    #[test]
+    fn test_scan_puts_readme_first() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("a.md"), "# A").unwrap();
+        fs::write(dir.join("README.md"), "# Readme").unwrap();
+        fs::write(dir.join("docs/b.markdown"), "# B").unwrap();
+
+        let files: Vec<PathBuf> = scan_markdown_files(dir)
+            .into_iter()
+            .map(|p| p.strip_prefix(dir).unwrap().to_path_buf())
+            .collect();
+
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("a.md"),
+                PathBuf::from("docs/b.markdown"),
+            ]
+        );
+
+        let subdirs = list_subdirectories(dir);
+        assert_eq!(subdirs, vec![dir.join("docs")]);
+    }
+
+    fn sample_tree() -> FileTree {
+        let root = Path::new("/docs");
+        let files: Vec<PathBuf> = [
+            "guide/intro.md",
+            "guide/deep/more.md",
+            "index.md",
+            "README.md",
+        ]
+        .iter()
+        .map(|f| root.join(f))
+        .collect();
+        FileTree::from_files(root, &files)
+    }
+
+    fn visible_names(tree: &FileTree) -> Vec<String> {
+        tree.visible()
+            .iter()
+            .map(|e| format!("{}{}", "  ".repeat(e.depth), e.name))
+            .collect()
+    }
+
+    #[test]
+    fn test_file_tree_starts_collapsed() {
+        let tree = sample_tree();
+        assert_eq!(visible_names(&tree), vec!["guide", "README.md", "index.md"]);
+    }
+
+    #[test]
+    fn test_file_tree_expand_and_collapse() {
+        let mut tree = sample_tree();
+        tree.toggle(Path::new("/docs/guide"));
+        assert_eq!(
+            visible_names(&tree),
+            vec!["guide", "  deep", "  intro.md", "README.md", "index.md"]
+        );
+
+        tree.toggle(Path::new("/docs/guide"));
+        assert_eq!(visible_names(&tree), vec!["guide", "README.md", "index.md"]);
+    }
+
+    #[test]
+    fn test_file_tree_reveal() {
+        let mut tree = sample_tree();
+        tree.reveal(Path::new("/docs/guide/deep/more.md"));
+        assert!(tree.is_expanded(Path::new("/docs/guide")));
+        assert!(tree.is_expanded(Path::new("/docs/guide/deep")));
+        assert!(
+            tree.visible()
+                .iter()
+                .any(|e| e.path == Path::new("/docs/guide/deep/more.md"))
+        );
+    }
+
+    #[test]
+    fn test_walk_respects_gitignore_and_hidden() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::create_dir_all(dir.join(".hidden")).unwrap();
+        fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        fs::write(dir.join("README.md"), "# Readme").unwrap();
+        fs::write(dir.join("docs/guide.md"), "# Guide").unwrap();
+        fs::write(dir.join("docs/notes.txt"), "notes").unwrap();
+        fs::write(dir.join("target/generated.md"), "# Generated").unwrap();
+        fs::write(dir.join(".hidden/secret.md"), "# Secret").unwrap();
+
+        let files: Vec<PathBuf> = walk_markdown_files(dir)
+            .map(|p| p.strip_prefix(dir).unwrap().to_path_buf())
+            .collect();
+
+        assert_eq!(
+            files,
+            vec![PathBuf::from("README.md"), PathBuf::from("docs/guide.md")]
+        );
+    }
+}
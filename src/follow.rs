@@ -0,0 +1,198 @@
+use crate::events::WakingSender;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often a followed file is checked for new bytes
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Size of each read from the followed source
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Message streamed from a follow worker
+#[derive(Debug, PartialEq, Eq)]
+pub enum FollowMessage {
+    /// New text appended to the document
+    Append(String),
+    /// The whole document changed (initial read, or the file was truncated)
+    Replace(String),
+    /// Reading failed; the worker stops
+    Error(String),
+}
+
+/// Decode as much of `pending` as is valid UTF-8, keeping an incomplete
+/// trailing character for the next chunk. Invalid bytes become U+FFFD.
+pub fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let mut text = String::new();
+
+    loop {
+        match std::str::from_utf8(pending) {
+            Ok(valid) => {
+                text.push_str(valid);
+                pending.clear();
+                return text;
+            }
+            Err(e) => {
+                let valid_up_to = e.valid_up_to();
+                text.push_str(std::str::from_utf8(&pending[..valid_up_to]).unwrap_or_default());
+                match e.error_len() {
+                    // Incomplete character at the end: wait for more bytes
+                    None => {
+                        pending.drain(..valid_up_to);
+                        return text;
+                    }
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        pending.drain(..valid_up_to + len);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Stream stdin until EOF. The channel disconnects when input ends.
+pub fn follow_stdin(tx: WakingSender<FollowMessage>) {
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        let mut pending = Vec::new();
+
+        loop {
+            match stdin.read(&mut buf) {
+                Ok(0) => return,
+                Ok(n) => {
+                    pending.extend_from_slice(&buf[..n]);
+                    let text = take_utf8(&mut pending);
+                    if !text.is_empty() && tx.send(FollowMessage::Append(text)).is_err() {
+                        return;
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    let _ = tx.send(FollowMessage::Error(e.to_string()));
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// Stream a file like `tail -f`: send its content, then poll for appended
+/// bytes. A file that shrinks is re-read from the start.
+pub fn follow_file(path: PathBuf, tx: WakingSender<FollowMessage>) {
+    std::thread::spawn(move || {
+        let mut offset: u64 = 0;
+        let mut pending = Vec::new();
+        let mut first = true;
+
+        loop {
+            match read_from(&path, &mut offset, &mut pending) {
+                Ok(Some((text, truncated))) => {
+                    let message = if first || truncated {
+                        FollowMessage::Replace(text)
+                    } else {
+                        FollowMessage::Append(text)
+                    };
+                    if tx.send(message).is_err() {
+                        return;
+                    }
+                    first = false;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    let _ = tx.send(FollowMessage::Error(e.to_string()));
+                    return;
+                }
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// Read new bytes of a file from `offset`, returning the decoded text and
+/// whether the file was truncated (None when nothing changed)
+fn read_from(
+    path: &Path,
+    offset: &mut u64,
+    pending: &mut Vec<u8>,
+) -> std::io::Result<Option<(String, bool)>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+
+    let truncated = len < *offset;
+    if truncated {
+        *offset = 0;
+        pending.clear();
+    }
+    if len == *offset && !truncated {
+        return Ok(None);
+    }
+
+    file.seek(SeekFrom::Start(*offset))?;
+    let read = file.read_to_end(pending)?;
+    *offset += read as u64;
+
+    let text = take_utf8(pending);
+    if text.is_empty() && !truncated {
+        return Ok(None);
+    }
+    Ok(Some((text, truncated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_utf8_complete() {
+        let mut pending = "héllo".as_bytes().to_vec();
+        assert_eq!(take_utf8(&mut pending), "héllo");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_take_utf8_keeps_incomplete_char() {
+        let bytes = "aé".as_bytes();
+        let mut pending = bytes[..2].to_vec(); // 'a' + first byte of 'é'
+        assert_eq!(take_utf8(&mut pending), "a");
+        assert_eq!(pending, vec![bytes[1]]);
+
+        pending.push(bytes[2]);
+        assert_eq!(take_utf8(&mut pending), "é");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_take_utf8_replaces_invalid_bytes() {
+        let mut pending = vec![b'a', 0xff, b'b'];
+        assert_eq!(take_utf8(&mut pending), "a\u{fffd}b");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_read_from_appends_and_detects_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("follow.md");
+        std::fs::write(&path, "# One\n").unwrap();
+
+        let mut offset = 0;
+        let mut pending = Vec::new();
+        let first = read_from(&path, &mut offset, &mut pending).unwrap();
+        assert_eq!(first, Some(("# One\n".to_string(), false)));
+        assert_eq!(read_from(&path, &mut offset, &mut pending).unwrap(), None);
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        std::io::Write::write_all(&mut file, b"more\n").unwrap();
+        let appended = read_from(&path, &mut offset, &mut pending).unwrap();
+        assert_eq!(appended, Some(("more\n".to_string(), false)));
+
+        std::fs::write(&path, "new").unwrap();
+        let replaced = read_from(&path, &mut offset, &mut pending).unwrap();
+        assert_eq!(replaced, Some(("new".to_string(), true)));
+    }
+}
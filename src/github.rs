@@ -268,6 +268,15 @@ impl GitHubFetcher {
         Err(FetchError::NotFound)
     }
 
+    /// Get previously fetched content for a URL without fetching
+    pub fn cached(&self, url: &str) -> Option<String> {
+        self.cache
+            .lock()
+            .unwrap()
+            .get(url)
+            .map(|cached| cached.content.clone())
+    }
+
     /// Clear the cache
     #[allow(dead_code)]
     pub fn clear_cache(&self) {
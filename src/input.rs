@@ -1,4 +1,4 @@
-use crate::app::{AppState, FocusedPanel, InputMode};
+use crate::app::{AppState, DragTarget, FocusedPanel, InputMode};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use tui_textarea::Input;
 
@@ -10,6 +10,11 @@ pub fn handle_key(state: &mut AppState, key: KeyEvent, page_size: usize) {
         return;
     }
 
+    if state.show_syntax_theme_picker {
+        handle_syntax_theme_picker(state, key);
+        return;
+    }
+
     if state.show_settings {
         handle_settings_overlay(state, key);
         return;
@@ -46,11 +51,17 @@ pub fn handle_key(state: &mut AppState, key: KeyEvent, page_size: usize) {
         return;
     }
 
+    if state.show_global_search {
+        handle_global_search(state, key);
+        return;
+    }
+
     match state.mode {
         InputMode::Normal => handle_normal_mode(state, key, page_size),
         InputMode::Search => handle_search_mode(state, key),
         InputMode::SplitCommand => handle_split_command(state, key),
-        InputMode::UrlInput | InputMode::BookmarkName => {}
+        InputMode::Command => handle_command_mode(state, key),
+        InputMode::UrlInput | InputMode::BookmarkName | InputMode::GlobalSearch => {}
     }
 }
 
@@ -69,8 +80,9 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent, page_size: usize) {
         // Settings
         KeyCode::Char('S') => state.show_settings = true,
 
-        // Outline toggle
+        // Outline / file tree toggles
         KeyCode::Char('b') => state.toggle_outline(),
+        KeyCode::Char('t') => state.toggle_file_tree(),
 
         // Panel focus
         KeyCode::Tab => state.cycle_focus(),
@@ -79,35 +91,40 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent, page_size: usize) {
         KeyCode::Char('j') | KeyCode::Down => match state.focused_panel {
             FocusedPanel::Content => state.scroll_down(),
             FocusedPanel::Outline => state.outline_down(),
+            FocusedPanel::FileTree => state.file_tree_down(),
         },
         KeyCode::Char('k') | KeyCode::Up => match state.focused_panel {
             FocusedPanel::Content => state.scroll_up(),
             FocusedPanel::Outline => state.outline_up(),
+            FocusedPanel::FileTree => state.file_tree_up(),
         },
 
-        // Horizontal navigation (content only, when wrap off) or outline resize
-        KeyCode::Char('h') | KeyCode::Left => {
-            if state.focused_panel == FocusedPanel::Content {
-                state.scroll_left();
-            } else if state.focused_panel == FocusedPanel::Outline {
+        // Horizontal navigation (content only, when wrap off), outline resize,
+        // or file tree collapse/expand
+        KeyCode::Char('h') | KeyCode::Left => match state.focused_panel {
+            FocusedPanel::Content => state.scroll_left(),
+            FocusedPanel::Outline => {
                 state.outline_width = state.outline_width.saturating_sub(2).max(16);
             }
-        }
-        KeyCode::Char('l') | KeyCode::Right => {
-            if state.focused_panel == FocusedPanel::Content {
-                state.scroll_right();
-            } else if state.focused_panel == FocusedPanel::Outline {
+            FocusedPanel::FileTree => state.file_tree_collapse(),
+        },
+        KeyCode::Char('l') | KeyCode::Right => match state.focused_panel {
+            FocusedPanel::Content => state.scroll_right(),
+            FocusedPanel::Outline => {
                 state.outline_width = (state.outline_width + 2).min(60);
             }
-        }
-        // Outline resize (alternative keys)
+            FocusedPanel::FileTree => state.file_tree_expand(),
+        },
+        // Sidebar resize (alternative keys)
+        #[allow(clippy::collapsible_match)]
         KeyCode::Char('<') | KeyCode::Char('[') => {
-            if state.show_outline {
+            if state.sidebar_visible() {
                 state.outline_width = state.outline_width.saturating_sub(4).max(16);
             }
         }
+        #[allow(clippy::collapsible_match)]
         KeyCode::Char('>') | KeyCode::Char(']') => {
-            if state.show_outline {
+            if state.sidebar_visible() {
                 state.outline_width = (state.outline_width + 4).min(60);
             }
         }
@@ -124,14 +141,15 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent, page_size: usize) {
         KeyCode::Home => state.go_to_top(),
         KeyCode::End => state.go_to_bottom(),
 
-        // Jump to heading (outline) or follow link (content)
-        KeyCode::Enter => {
-            if state.focused_panel == FocusedPanel::Outline {
-                state.jump_to_heading();
-            } else {
-                state.follow_link();
-            }
-        }
+        // Jump to heading (outline), open file (tree) or follow link (content)
+        KeyCode::Enter => match state.focused_panel {
+            FocusedPanel::Outline => state.jump_to_heading(),
+            FocusedPanel::FileTree => state.file_tree_activate(),
+            FocusedPanel::Content => state.follow_link(),
+        },
+
+        // Project-wide search (must come before plain f)
+        KeyCode::Char('f') if ctrl => state.start_project_search(),
 
         // Follow link (vim-style gf = "go file")
         KeyCode::Char('f') if state.focused_panel == FocusedPanel::Content => {
@@ -148,6 +166,10 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent, page_size: usize) {
         KeyCode::Char('/') => state.start_search(),
         KeyCode::Char('n') => state.next_match(),
         KeyCode::Char('N') => state.prev_match(),
+        KeyCode::Char('F') => state.start_global_search(),
+
+        // Command line
+        KeyCode::Char(':') => state.start_command(),
 
         // Display toggles (Ctrl+w must come before plain w)
         KeyCode::Char('w') if ctrl => state.mode = InputMode::SplitCommand,
@@ -187,10 +209,26 @@ fn handle_search_mode(state: &mut AppState, key: KeyEvent) {
         KeyCode::Enter => state.apply_search(),
         KeyCode::Esc => state.cancel_search(),
         KeyCode::Char('r') if ctrl => state.toggle_regex(),
+        KeyCode::Char('t') if ctrl => state.toggle_whole_word(),
+        KeyCode::Up => state.search_history_older(),
+        KeyCode::Down => state.search_history_newer(),
         _ => {
-            // Forward to textarea
+            // Forward to textarea, searching as the query changes
             let input = Input::from(key);
-            state.current_pane_mut().search_textarea.input(input);
+            if state.current_pane_mut().search_textarea.input(input) {
+                state.update_search();
+            }
+        }
+    }
+}
+
+/// Handle command line input (after `:`)
+fn handle_command_mode(state: &mut AppState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => state.submit_command(),
+        KeyCode::Esc => state.cancel_command(),
+        _ => {
+            state.command_textarea.input(Input::from(key));
         }
     }
 }
@@ -221,7 +259,7 @@ fn handle_help_overlay(state: &mut AppState, key: KeyEvent) {
 
 /// Handle settings overlay input
 fn handle_settings_overlay(state: &mut AppState, key: KeyEvent) {
-    const NUM_SETTINGS: usize = 7; // 6 toggles + Save
+    const NUM_SETTINGS: usize = 9; // 8 toggles + Save
 
     match key.code {
         KeyCode::Esc | KeyCode::Char('S') => {
@@ -245,23 +283,24 @@ fn handle_settings_overlay(state: &mut AppState, key: KeyEvent) {
 
 /// Handle file picker overlay input
 fn handle_file_picker(state: &mut AppState, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
     match key.code {
-        KeyCode::Esc | KeyCode::Char('o') => {
-            state.close_file_picker();
-        }
-        KeyCode::Char('j') | KeyCode::Down => {
-            state.file_picker_down();
+        KeyCode::Esc => state.close_file_picker(),
+        KeyCode::Enter => state.open_selected_file(),
+        KeyCode::Down => state.file_picker_down(),
+        KeyCode::Up => state.file_picker_up(),
+        KeyCode::Char('n') if ctrl => state.file_picker_down(),
+        KeyCode::Char('p') if ctrl => state.file_picker_up(),
+        KeyCode::Backspace if state.file_picker_textarea.is_empty() => {
+            state.file_picker_parent();
         }
-        KeyCode::Char('k') | KeyCode::Up => {
-            state.file_picker_up();
-        }
-        KeyCode::Enter => {
-            state.open_selected_file();
-        }
-        KeyCode::Char('q') => {
-            state.close_file_picker();
+        _ => {
+            let input = Input::from(key);
+            if state.file_picker_textarea.input(input) {
+                state.filter_file_picker();
+            }
         }
-        _ => {}
     }
 }
 
@@ -284,6 +323,17 @@ fn handle_buffer_list(state: &mut AppState, key: KeyEvent) {
     }
 }
 
+/// Handle code theme picker input
+fn handle_syntax_theme_picker(state: &mut AppState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => state.close_syntax_theme_picker(),
+        KeyCode::Char('j') | KeyCode::Down => state.syntax_theme_picker_down(),
+        KeyCode::Char('k') | KeyCode::Up => state.syntax_theme_picker_up(),
+        KeyCode::Enter => state.select_syntax_theme(),
+        _ => {}
+    }
+}
+
 /// Toggle a setting by index
 fn toggle_setting(state: &mut AppState, index: usize) {
     match index {
@@ -292,8 +342,10 @@ fn toggle_setting(state: &mut AppState, index: usize) {
         2 => state.toggle_outline(),
         3 => state.toggle_line_numbers(),
         4 => state.toggle_syntax_highlighting(),
-        5 => state.toggle_auto_reload(),
-        6 => state.save_config(),
+        5 => state.open_syntax_theme_picker(),
+        6 => state.toggle_auto_reload(),
+        7 => state.toggle_jump_to_change(),
+        8 => state.save_config(),
         _ => {}
     }
 }
@@ -363,8 +415,34 @@ fn handle_bookmark_name_input(state: &mut AppState, key: KeyEvent) {
     }
 }
 
+/// Handle global search overlay input
+fn handle_global_search(state: &mut AppState, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key.code {
+        KeyCode::Esc => state.close_global_search(),
+        KeyCode::Enter => state.submit_global_search(),
+        KeyCode::Tab => state.toggle_search_scope(),
+        KeyCode::Down => state.global_search_down(),
+        KeyCode::Up => state.global_search_up(),
+        KeyCode::Char('n') if ctrl => state.global_search_down(),
+        KeyCode::Char('p') if ctrl => state.global_search_up(),
+        KeyCode::Char('r') if ctrl => state.toggle_global_search_regex(),
+        KeyCode::Char('t') if ctrl => state.toggle_global_search_whole_word(),
+        _ => {
+            let input = Input::from(key);
+            if state.global_search_textarea.input(input) {
+                state.update_global_search();
+            }
+        }
+    }
+}
+
 /// Handle mouse input
 pub fn handle_mouse(state: &mut AppState, mouse: MouseEvent, _page_size: usize) {
+    let x = mouse.column;
+    let y = mouse.row;
+
     match mouse.kind {
         MouseEventKind::ScrollUp => {
             for _ in 0..3 {
@@ -376,25 +454,67 @@ pub fn handle_mouse(state: &mut AppState, mouse: MouseEvent, _page_size: usize)
                 state.scroll_down();
             }
         }
-        MouseEventKind::Down(MouseButton::Left) => {
-            // Check if click is in outline area
-            let x = mouse.column;
-            let y = mouse.row;
-
-            if state.show_outline && x < state.outline_area.right() && y >= state.outline_area.y {
-                state.focused_panel = FocusedPanel::Outline;
-            } else {
-                state.focused_panel = FocusedPanel::Content;
-
-                // Check which pane was clicked (for split view)
-                for (i, area) in state.content_areas.iter().enumerate() {
-                    if x >= area.x && x < area.right() && y >= area.y && y < area.bottom() {
-                        state.active_pane = i;
-                        break;
-                    }
-                }
-            }
-        }
+        MouseEventKind::Down(MouseButton::Left) => handle_left_click(state, x, y),
+        MouseEventKind::Drag(MouseButton::Left) => state.drag_to(x, y),
+        MouseEventKind::Up(MouseButton::Left) => state.drag_target = None,
         _ => {}
     }
 }
+
+/// Handle a left click: start drags, focus panels, follow links
+fn handle_left_click(state: &mut AppState, x: u16, y: u16) {
+    let tree = state.file_tree_area;
+    let outline = state.outline_area;
+
+    if state.show_file_tree && x < tree.right() && y >= tree.y && y < tree.bottom() {
+        // Right border of the sidebar resizes it
+        if x == tree.right().saturating_sub(1) {
+            state.drag_target = Some(DragTarget::OutlineBorder);
+        } else {
+            state.focused_panel = FocusedPanel::FileTree;
+            state.click_file_tree(y);
+        }
+        return;
+    }
+
+    if state.show_outline && x < outline.right() && y >= outline.y {
+        // Right border of the outline resizes it
+        if x == outline.right().saturating_sub(1) {
+            state.drag_target = Some(DragTarget::OutlineBorder);
+        } else {
+            state.focused_panel = FocusedPanel::Outline;
+            state.click_outline(y);
+        }
+        return;
+    }
+
+    state.focused_panel = FocusedPanel::Content;
+
+    if state.is_on_split_border(x, y) {
+        state.drag_target = Some(DragTarget::SplitBorder);
+        return;
+    }
+
+    // Check which pane was clicked (for split view)
+    let Some(pane_idx) = state
+        .content_areas
+        .iter()
+        .position(|area| x >= area.x && x < area.right() && y >= area.y && y < area.bottom())
+    else {
+        return;
+    };
+    state.active_pane = pane_idx;
+
+    // Scrollbar occupies the last column of the text area
+    if let Some(text_area) = state.pane_text_areas.get(pane_idx).copied() {
+        let on_scrollbar =
+            x == text_area.right().saturating_sub(1) && y >= text_area.y && y < text_area.bottom();
+        if on_scrollbar && state.pane_has_scrollbar(pane_idx) {
+            state.drag_target = Some(DragTarget::Scrollbar(pane_idx));
+            state.scroll_to_scrollbar_row(pane_idx, y);
+            return;
+        }
+    }
+
+    state.click_content(pane_idx, x, y);
+}
@@ -0,0 +1,34 @@
+//! Markdown rendering for ratatui, as used by the barkdocs viewer.
+//!
+//! Parse markdown into a [`Document`], render it to styled lines with a
+//! [`Theme`] (and optionally a [`SyntaxHighlighter`] for code blocks), then
+//! display the lines with the [`MarkdownView`] widget.
+//!
+//! ```
+//! use barkdocs::{Document, MarkdownView, MarkdownViewState, SyntaxHighlighter, Theme};
+//! use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+//!
+//! let theme = Theme::by_name("nord");
+//! let highlighter = SyntaxHighlighter::new(theme.syntax_theme);
+//! let mut document = Document::parse("# Hello\n\n```rust\nfn main() {}\n```\n");
+//! let lines = document.render_with_highlighting(&theme, Some(&highlighter));
+//!
+//! let mut state = MarkdownViewState::new();
+//! state.search(&lines, "main", Default::default()).unwrap();
+//!
+//! let area = Rect::new(0, 0, 40, 10);
+//! let mut buf = Buffer::empty(area);
+//! MarkdownView::new(&lines, &theme).render(area, &mut buf, &mut state);
+//! ```
+
+pub mod export;
+pub mod markdown;
+pub mod outline;
+pub mod search;
+pub mod theme;
+pub mod view;
+
+pub use markdown::{Block, Document, Heading, Link, SyntaxHighlighter};
+pub use search::SearchOptions;
+pub use theme::Theme;
+pub use view::{MarkdownView, MarkdownViewState};
@@ -1,71 +1,163 @@
 mod app;
+mod cli;
 mod config;
+mod events;
+mod files;
+mod follow;
 mod github;
 mod input;
-mod markdown;
+mod reload;
 mod storage;
-mod theme;
+mod syntaxes;
 mod ui;
+mod watch;
 
 use anyhow::Result;
-use app::AppState;
+use app::{AppState, StartupJump};
+use barkdocs::export::{self, ExportFormat};
+use barkdocs::markdown::{Block, Document};
+use barkdocs::outline;
+use cli::{CliArgs, ColorMode, Subcommand, Target};
 use config::Config;
-use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind};
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
+use events::{AppEvent, Worker};
+use github::GitHubFetcher;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
-use std::io;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Instant;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 fn main() -> Result<()> {
-    let args: Vec<String> = std::env::args().collect();
+    let cli = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("barkdocs: {}", e);
+            eprintln!("Try 'barkdocs --help' for more information.");
+            std::process::exit(2);
+        }
+    };
 
-    // Handle --help, --version
-    if args.len() >= 2 {
-        match args[1].as_str() {
-            "--help" | "-h" => {
-                print_help();
-                return Ok(());
-            }
-            "--version" | "-V" => {
-                println!("barkdocs {}", VERSION);
-                return Ok(());
-            }
-            _ => {}
+    if cli.help {
+        print_help();
+        return Ok(());
+    }
+    if cli.version {
+        println!("barkdocs {}", VERSION);
+        return Ok(());
+    }
+
+    // Load config (command-line options override file and environment)
+    let mut config = match &cli.config_path {
+        Some(path) => Config::load_from(path).unwrap_or_else(|e| {
+            eprintln!("barkdocs: {}", e);
+            std::process::exit(2);
+        }),
+        None => Config::load(),
+    };
+    cli.apply_to(&mut config);
+
+    if cli.json {
+        if !write_json(&cli) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(command) = &cli.command {
+        if !run_subcommand(&cli, &config, command) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if cli.print || cli.export.is_some() {
+        if !write_documents(&cli, &config) {
+            std::process::exit(1);
         }
+        return Ok(());
     }
 
-    // Load config
-    let config = Config::load();
     let mut state = AppState::new(&config);
+    state.config_path = cli.config_path.clone();
+
+    let mut jump = StartupJump {
+        line: None,
+        heading: cli.heading.clone(),
+        search: cli.search.clone(),
+    };
+
+    // Determine what to open (files, directories or URLs)
+    let targets = &cli.targets;
+
+    if !targets.is_empty() {
+        for target in targets {
+            if target.is_url() {
+                if targets.len() == 1 {
+                    // Use non-blocking fetch for startup URL
+                    state.start_url_fetch(&target.location);
+                    jump.line = target.line;
+                } else {
+                    // Fetched in the background into its own buffer
+                    state.open_url_buffer(&target.location);
+                }
+                continue;
+            }
 
-    // Determine what to open (file or URL)
-    if args.len() >= 2 && !args[1].starts_with('-') {
-        let arg = &args[1];
+            if target.location == "-" {
+                if cli.follow {
+                    state.start_follow_stdin();
+                } else {
+                    load_stdin(&mut state);
+                }
+                continue;
+            }
 
-        // Check if it's a URL
-        if arg.starts_with("http://") || arg.starts_with("https://") {
-            // Use non-blocking fetch for startup URL
-            state.start_url_fetch(arg);
-        } else {
-            // Local file path
-            let path = PathBuf::from(arg);
-            if let Err(e) = state.load_file(&path) {
+            let path = PathBuf::from(&target.location);
+            if path.is_dir() {
+                // Directory: browse it in the file tree, showing its README if any
+                if let Some(readme) = find_readme(&path)
+                    && let Err(e) = state.load_file(&readme)
+                {
+                    state.status_message = Some(format!("Error loading file: {}", e));
+                }
+                state.open_file_tree(&path);
+            } else if let Err(e) = state.load_file(&path) {
+                // Local file path
                 state.status_message = Some(format!("Error loading file: {}", e));
+            } else if cli.follow && state.follow_path.is_none() {
+                state.start_follow_file(&path);
+            } else if let Some(line) = target.line {
+                if targets.len() == 1 {
+                    // Waits for the document if it renders in the background
+                    jump.line = Some(line);
+                } else {
+                    state.go_to_source_line(line);
+                }
             }
         }
+
+        // Show the first document, the rest stay available as buffers
+        if targets.len() > 1 && !state.buffers.is_empty() {
+            state.load_from_buffer(0);
+        }
+    } else if !io::stdin().is_terminal() {
+        // Piped input: act as a pager
+        if cli.follow {
+            state.start_follow_stdin();
+        } else {
+            load_stdin(&mut state);
+        }
     } else {
         // Try README.md in current directory
-        let candidates = ["README.md", "readme.md", "README.MD", "Readme.md"];
-        let readme = candidates.iter().map(PathBuf::from).find(|p| p.exists());
-
-        if let Some(path) = readme {
+        if let Some(path) = find_readme(Path::new(".")) {
             if let Err(e) = state.load_file(&path) {
                 state.status_message = Some(format!("Error loading file: {}", e));
             }
@@ -75,10 +167,12 @@ fn main() -> Result<()> {
         }
     }
 
+    state.apply_startup_jump(jump);
+
     // Terminal setup
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -89,7 +183,7 @@ fn main() -> Result<()> {
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic| {
         let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
         original_hook(panic);
     }));
 
@@ -101,7 +195,11 @@ fn main() -> Result<()> {
 
     // Cleanup
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
     terminal.show_cursor()?;
 
     result
@@ -111,34 +209,89 @@ fn run_event_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     state: &mut AppState,
 ) -> Result<()> {
+    let (tx, rx) = mpsc::channel();
+    state.waker.connect(tx.clone());
+    events::spawn_terminal_reader(tx);
+
+    // Collect anything that finished before the loop was listening
+    state.check_follow();
+    state.check_fetch_complete();
+    state.check_project_search();
+    state.check_render_jobs();
+    state.check_highlighter();
+
+    let mut dirty = true;
     loop {
-        // Draw
-        terminal.draw(|frame| ui::draw(frame, state))?;
-
-        // Calculate page size
-        let page_size = terminal.size()?.height.saturating_sub(4) as usize;
+        // Redraw only when something changed
+        if dirty {
+            state.highlight_visible_code();
+            terminal.draw(|frame| ui::draw(frame, state))?;
+            dirty = false;
+        }
 
-        // Wait for event with timeout (for smooth UI)
-        if event::poll(Duration::from_millis(100))? {
-            match event::read()? {
-                Event::Key(key) if key.kind == KeyEventKind::Press => {
-                    input::handle_key(state, key, page_size);
+        // Sleep until an event arrives or a timer (debounced reload,
+        // change highlight) is due
+        let event = match state.next_deadline() {
+            Some(deadline) => {
+                match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(event) => Some(event),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
                 }
-                Event::Mouse(mouse) => {
-                    input::handle_mouse(state, mouse, page_size);
-                }
-                Event::Resize(_, _) => {
-                    // Terminal handles redraw automatically
-                }
-                _ => {}
             }
+            None => match rx.recv() {
+                Ok(event) => Some(event),
+                Err(_) => break,
+            },
+        };
+
+        match event {
+            Some(AppEvent::Terminal(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                let page_size = terminal.size()?.height.saturating_sub(4) as usize;
+                input::handle_key(state, key, page_size);
+                dirty = true;
+            }
+            Some(AppEvent::Terminal(Event::Mouse(mouse))) => {
+                let page_size = terminal.size()?.height.saturating_sub(4) as usize;
+                input::handle_mouse(state, mouse, page_size);
+                dirty = true;
+            }
+            Some(AppEvent::Terminal(Event::Resize(_, _))) => {
+                // Terminal resizes its buffers on the next draw
+                dirty = true;
+            }
+            Some(AppEvent::Terminal(_)) => {}
+            Some(AppEvent::Worker(Worker::FileWatch)) => {}
+            Some(AppEvent::Worker(Worker::Follow)) => {
+                // Apply text streamed in follow mode
+                state.check_follow();
+                dirty = true;
+            }
+            Some(AppEvent::Worker(Worker::Fetch)) => {
+                state.check_fetch_complete();
+                dirty = true;
+            }
+            Some(AppEvent::Worker(Worker::ProjectSearch)) => {
+                state.check_project_search();
+                dirty = true;
+            }
+            Some(AppEvent::Worker(Worker::Render)) => {
+                state.check_render_jobs();
+                dirty = true;
+            }
+            Some(AppEvent::Worker(Worker::Highlighter)) => {
+                // Code blocks in view get highlighted before the redraw
+                state.check_highlighter();
+                dirty = true;
+            }
+            // A timer is due
+            None => dirty = true,
         }
 
-        // Check for file changes (live reload)
-        state.check_file_changed();
-
-        // Check for completed URL fetches
-        state.check_fetch_complete();
+        // Apply file changes that settled (live reload)
+        if state.check_file_changed() {
+            dirty = true;
+        }
 
         if state.should_quit {
             break;
@@ -148,19 +301,255 @@ fn run_event_loop(
     Ok(())
 }
 
+/// Render documents for `--print` and `--export`, writing them to stdout or
+/// `--output`. Returns false if any failed.
+fn write_documents(cli: &CliArgs, config: &Config) -> bool {
+    let fetcher = GitHubFetcher::new();
+    let mut ok = true;
+    let mut sources = Vec::new();
+
+    for target in input_targets(cli) {
+        match read_source(&target, &fetcher) {
+            Ok(source) => sources.push((target.location, source)),
+            Err(e) => {
+                eprintln!("barkdocs: {}: {}", target.location, e);
+                ok = false;
+            }
+        }
+    }
+
+    let output = render_documents(cli, config, &sources);
+    write_output(cli, &output) && ok
+}
+
+/// Run a `toc`, `section` or `links` query. Returns false on failure.
+fn run_subcommand(cli: &CliArgs, config: &Config, command: &Subcommand) -> bool {
+    let target = input_targets(cli).remove(0);
+    let source = match read_source(&target, &GitHubFetcher::new()) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("barkdocs: {}: {}", target.location, e);
+            return false;
+        }
+    };
+    let document = Document::parse(&source);
+
+    let output = match command {
+        Subcommand::Toc => outline::toc(&document.headings),
+        Subcommand::Links => document
+            .links
+            .iter()
+            .map(|link| format!("{}\t{}\t{}\n", link.source_line + 1, link.url, link.text))
+            .collect(),
+        Subcommand::Section { heading } => {
+            let Some(index) = outline::find_heading(&document.headings, heading) else {
+                eprintln!("barkdocs: heading not found: {}", heading);
+                return false;
+            };
+            let section = outline::section_source(&source, &document.headings, index);
+            if cli.print || cli.export.is_some() {
+                render_documents(cli, config, &[(target.location, section)])
+            } else {
+                section
+            }
+        }
+    };
+
+    write_output(cli, &output)
+}
+
+/// Print the parsed document as JSON for `--json`. Returns false on failure.
+fn write_json(cli: &CliArgs) -> bool {
+    let target = input_targets(cli).remove(0);
+    let source = match read_source(&target, &GitHubFetcher::new()) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("barkdocs: {}: {}", target.location, e);
+            return false;
+        }
+    };
+
+    let document = Document::parse(&source);
+    match serde_json::to_string_pretty(&document) {
+        Ok(json) => write_output(cli, &(json + "\n")),
+        Err(e) => {
+            eprintln!("barkdocs: failed to serialize document: {}", e);
+            false
+        }
+    }
+}
+
+/// Targets for non-interactive modes: the command-line targets, or stdin
+/// when piped, or the README in the current directory
+fn input_targets(cli: &CliArgs) -> Vec<Target> {
+    if !cli.targets.is_empty() {
+        return cli.targets.clone();
+    }
+    let location = if io::stdin().is_terminal() { "." } else { "-" };
+    vec![Target {
+        location: location.to_string(),
+        line: None,
+    }]
+}
+
+/// Render (name, markdown) pairs in the `--export` format, or as ANSI/plain
+/// text for `--print`
+fn render_documents(cli: &CliArgs, config: &Config, sources: &[(String, String)]) -> String {
+    let to_terminal = cli.output.is_none() && io::stdout().is_terminal();
+    let format = cli.export.unwrap_or_else(|| {
+        let color = match cli.color {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => to_terminal && std::env::var_os("NO_COLOR").is_none(),
+        };
+        if color {
+            ExportFormat::Ansi
+        } else {
+            ExportFormat::Text
+        }
+    });
+    let width = cli.width.unwrap_or_else(|| {
+        crossterm::terminal::size()
+            .ok()
+            .filter(|_| to_terminal)
+            .map_or(export::DEFAULT_WIDTH, |(width, _)| width)
+    });
+
+    let theme = config.get_theme();
+    // Loading syntaxes takes a moment, so only do it for documents with code
+    let mut highlighter = None;
+    let mut output = String::new();
+    let mut html_bodies = Vec::new();
+
+    for (_, source) in sources {
+        let mut document = Document::parse(source);
+        let has_code = document
+            .blocks
+            .iter()
+            .any(|block| matches!(block, Block::CodeBlock { .. }));
+        if config.syntax_highlighting && has_code && highlighter.is_none() {
+            let (mut loaded, error) = syntaxes::load_highlighter();
+            if let Some(error) = error {
+                eprintln!("barkdocs: {}", error);
+            }
+            loaded.set_theme(&config.get_syntax_theme());
+            highlighter = Some(loaded);
+        }
+        let lines = document.render_with_highlighting(&theme, highlighter.as_ref());
+        match format {
+            ExportFormat::Html => html_bodies.push(export::html_body(&document, &lines)),
+            ExportFormat::Text => output.push_str(&export::to_ansi(&lines, width, false)),
+            ExportFormat::Ansi => output.push_str(&export::to_ansi(&lines, width, true)),
+        }
+    }
+
+    if format == ExportFormat::Html {
+        let title = sources
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        output = export::html_page(&title, &html_bodies, &theme);
+    }
+
+    output
+}
+
+/// Write output to `--output` or stdout. Returns false on failure.
+fn write_output(cli: &CliArgs, output: &str) -> bool {
+    let result = match &cli.output {
+        Some(path) => std::fs::write(path, output),
+        None => {
+            let mut stdout = io::stdout().lock();
+            stdout
+                .write_all(output.as_bytes())
+                .and_then(|_| stdout.flush())
+        }
+    };
+    match result {
+        Ok(()) => true,
+        // A closed pipe (e.g. `| head`) is not an error
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => true,
+        Err(e) => {
+            let target = cli
+                .output
+                .as_ref()
+                .map_or("stdout".to_string(), |p| p.display().to_string());
+            eprintln!("barkdocs: {}: {}", target, e);
+            false
+        }
+    }
+}
+
+/// Read the markdown source of a file, a directory's README, a URL or `-` (stdin)
+fn read_source(target: &Target, fetcher: &GitHubFetcher) -> Result<String, String> {
+    if target.is_url() {
+        return fetcher.fetch(&target.location).map_err(|e| e.to_string());
+    }
+    if target.location == "-" {
+        return io::read_to_string(io::stdin()).map_err(|e| e.to_string());
+    }
+
+    let mut path = PathBuf::from(&target.location);
+    if path.is_dir() {
+        path = find_readme(&path).ok_or("no README found")?;
+    }
+    std::fs::read_to_string(&path).map_err(|e| e.to_string())
+}
+
+/// Read the document from stdin.
+///
+/// Keyboard input still works afterwards: crossterm reads events from
+/// `/dev/tty` when stdin is not a terminal.
+fn load_stdin(state: &mut AppState) {
+    match io::read_to_string(io::stdin()) {
+        Ok(content) => state.load_stdin(&content),
+        Err(e) => state.status_message = Some(format!("Error reading stdin: {}", e)),
+    }
+}
+
+/// Find a README file directly inside a directory
+fn find_readme(dir: &Path) -> Option<PathBuf> {
+    let candidates = ["README.md", "readme.md", "README.MD", "Readme.md"];
+    candidates.iter().map(|c| dir.join(c)).find(|p| p.exists())
+}
+
 fn print_help() {
     println!(
         r#"barkdocs - A keyboard-driven TUI markdown viewer
 
 USAGE:
-    barkdocs [OPTIONS] [FILE|URL]
+    barkdocs [OPTIONS] [FILE|DIR|URL]...
+    command | barkdocs [OPTIONS]
+    barkdocs toc [FILE]                 Print the heading tree with anchors
+    barkdocs section FILE HEADING       Print one section's markdown
+                                        (rendered with --print/--export)
+    barkdocs links [FILE]               List links as LINE<TAB>URL<TAB>TEXT
 
 ARGS:
-    [FILE|URL]  Markdown file or GitHub URL to view (defaults to README.md)
+    [FILE|DIR|URL]...  Markdown files, directory to browse, or GitHub URLs;
+                       each opens as a buffer (defaults to README.md,
+                       or stdin when input is piped; `-` reads stdin)
 
 OPTIONS:
-    -h, --help       Print help information
-    -V, --version    Print version information
+    -h, --help              Print help information
+    -V, --version           Print version information
+    --theme NAME            Use a theme
+    --outline, --no-outline Show or hide the outline panel
+    --wrap, --no-wrap       Enable or disable line wrap
+    --line-numbers          Show line numbers (--no-line-numbers to hide)
+    +LINE, FILE:LINE        Open at a source line
+    --search PATTERN        Open with a search applied
+    --heading TEXT          Open at the first matching heading
+    --config PATH           Use an alternate config file
+    --follow                Re-render the file or stdin as it grows (tail -f)
+    -p, --print             Print the rendered document to stdout and exit
+    --width N               Wrap printed output at N columns (default: terminal width)
+    --color WHEN            Color printed output: auto, always or never
+                            (auto: only on a terminal without NO_COLOR set)
+    --export FORMAT         Export to html, text or ansi and exit
+    --json                  Print the parsed document structure as JSON
+    -o, --output PATH       Write --print/--export/--json output to a file
 
 NAVIGATION:
     j/k, ↑/↓         Scroll up/down
@@ -170,6 +559,7 @@ NAVIGATION:
     /                Start search
     n/N              Next/prev search match
     b                Toggle outline panel
+    t                Toggle file tree panel
     w                Toggle line wrap
     Ctrl+W,v         Split vertical
     Ctrl+W,s         Split horizontal
@@ -196,6 +586,7 @@ CONFIG:
 DATA:
     ~/.local/share/barkdocs/history.json
     ~/.local/share/barkdocs/bookmarks.json
+    ~/.local/share/barkdocs/search_history.json
 
 ENVIRONMENT:
     BARKDOCS_THEME        Override theme
@@ -1,12 +1,19 @@
+use crate::outline;
 use crate::theme::Theme;
 use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use syntect::easy::HighlightLines;
 use syntect::highlighting::{Style as SyntectStyle, ThemeSet};
 use syntect::parsing::SyntaxSet;
 
-/// Syntax highlighter for code blocks
+/// Syntax highlighter for code blocks.
+///
+/// Loading the syntax and theme sets takes a moment; interactive apps can
+/// build it on a background thread and render code plain until it is ready
+/// (see [`Document::render_deferred`]).
 pub struct SyntaxHighlighter {
     syntax_set: SyntaxSet,
     theme_set: ThemeSet,
@@ -28,14 +35,23 @@ impl SyntaxHighlighter {
         }
     }
 
-    /// Set the highlighting theme
-    #[allow(dead_code)]
+    /// Highlighter using the given syntaxes and themes, such as the defaults
+    /// extended with custom ones. Syntaxes must be built for lines that
+    /// include their newline.
+    pub fn from_sets(syntax_set: SyntaxSet, theme_set: ThemeSet, theme_name: &str) -> Self {
+        Self {
+            syntax_set,
+            theme_set,
+            theme_name: theme_name.to_string(),
+        }
+    }
+
+    /// Set the highlighting theme (see [`Theme::syntax_theme`])
     pub fn set_theme(&mut self, theme_name: &str) {
         self.theme_name = theme_name.to_string();
     }
 
     /// Get available theme names
-    #[allow(dead_code)]
     pub fn available_themes(&self) -> Vec<&str> {
         self.theme_set.themes.keys().map(|s| s.as_str()).collect()
     }
@@ -84,27 +100,102 @@ fn syntect_to_ratatui_color(color: syntect::highlighting::Color) -> Color {
     Color::Rgb(color.r, color.g, color.b)
 }
 
+/// Render the content lines of a code block (without the fences)
+fn render_code_lines(
+    code: &str,
+    language: Option<&str>,
+    theme: &Theme,
+    highlighter: Option<&SyntaxHighlighter>,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    if let Some(hl) = highlighter {
+        let highlighted = hl.highlight(code, language);
+        for highlighted_line in highlighted {
+            let mut line_spans = vec![Span::styled("  ", Style::default().bg(theme.code_block_bg))];
+            for (style, text) in highlighted_line {
+                let fg = syntect_to_ratatui_color(style.foreground);
+                let mut ratatui_style = Style::default().fg(fg).bg(theme.code_block_bg);
+                if style
+                    .font_style
+                    .contains(syntect::highlighting::FontStyle::BOLD)
+                {
+                    ratatui_style = ratatui_style.add_modifier(Modifier::BOLD);
+                }
+                if style
+                    .font_style
+                    .contains(syntect::highlighting::FontStyle::ITALIC)
+                {
+                    ratatui_style = ratatui_style.add_modifier(Modifier::ITALIC);
+                }
+                if style
+                    .font_style
+                    .contains(syntect::highlighting::FontStyle::UNDERLINE)
+                {
+                    ratatui_style = ratatui_style.add_modifier(Modifier::UNDERLINED);
+                }
+                line_spans.push(Span::styled(text, ratatui_style));
+            }
+            lines.push(Line::from(line_spans));
+        }
+    } else {
+        // Fallback: no highlighting
+        for code_line in code.lines() {
+            lines.push(Line::styled(
+                format!("  {}", code_line),
+                Style::default()
+                    .fg(theme.code_inline)
+                    .bg(theme.code_block_bg),
+            ));
+        }
+    }
+
+    lines
+}
+
 /// A heading extracted for the outline
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Heading {
     pub level: u8,
     pub text: String,
+    /// GitHub-style anchor, unique within the document
+    pub slug: String,
+    #[serde(skip)]
     pub line_number: usize,
+    /// Source line (0-based) of the heading, serialized 1-based as `line`
+    #[serde(rename = "line", serialize_with = "serialize_one_based")]
+    pub source_line: usize,
     /// Actual line in rendered output (set during rendering)
+    #[serde(skip)]
     pub rendered_line: usize,
 }
 
 /// A link found in the document
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Link {
     pub url: String,
-    #[allow(dead_code)]
     pub text: String,
+    #[serde(skip)]
     pub line_number: usize,
+    /// Source line (0-based) where the link starts, serialized 1-based as `line`
+    #[serde(rename = "line", serialize_with = "serialize_one_based")]
+    pub source_line: usize,
+}
+
+/// A link's position in the rendered output (set during rendering)
+#[derive(Clone, Debug)]
+pub struct RenderedLink {
+    pub url: String,
+    /// Rendered line index
+    pub line: usize,
+    /// Start column (display width) within the line
+    pub start: usize,
+    /// End column (exclusive)
+    pub end: usize,
 }
 
 /// Inline text style
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct SpanStyle {
     pub bold: bool,
     pub italic: bool,
@@ -114,20 +205,21 @@ pub struct SpanStyle {
 }
 
 /// A text span with styling
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct StyledSpan {
     pub text: String,
     pub style: SpanStyle,
 }
 
 /// List item content
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ListItem {
     pub spans: Vec<StyledSpan>,
 }
 
 /// A block of content
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 #[allow(clippy::enum_variant_names)]
 pub enum Block {
     Heading {
@@ -158,6 +250,47 @@ pub struct Document {
     pub blocks: Vec<Block>,
     pub headings: Vec<Heading>,
     pub links: Vec<Link>,
+    /// Link positions in rendered output (set during rendering)
+    pub rendered_links: Vec<RenderedLink>,
+    /// Source line (0-based) where each block starts, parallel to `blocks`
+    pub block_source_lines: Vec<usize>,
+    /// Rendered line where each block starts (set during rendering)
+    pub block_rendered_lines: Vec<usize>,
+    /// Code blocks rendered without syntax highlighting by
+    /// [`Document::render_deferred`], still waiting for [`Document::highlight_lines`]
+    pub unhighlighted_code: Vec<usize>,
+}
+
+/// Serialized as `blocks`, `headings` and `links`, each block carrying the
+/// 1-based source `line` it starts on (like `headings` and `links`)
+impl Serialize for Document {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct LocatedBlock<'a> {
+            #[serde(serialize_with = "serialize_one_based")]
+            line: &'a usize,
+            #[serde(flatten)]
+            block: &'a Block,
+        }
+
+        let blocks: Vec<LocatedBlock> = self
+            .blocks
+            .iter()
+            .zip(&self.block_source_lines)
+            .map(|(block, line)| LocatedBlock { line, block })
+            .collect();
+
+        let mut document = serializer.serialize_struct("Document", 3)?;
+        document.serialize_field("blocks", &blocks)?;
+        document.serialize_field("headings", &self.headings)?;
+        document.serialize_field("links", &self.links)?;
+        document.end()
+    }
+}
+
+/// Serialize a 0-based line number as the 1-based number editors show
+fn serialize_one_based<S: Serializer>(line: &usize, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(*line as u64 + 1)
 }
 
 impl Document {
@@ -167,12 +300,19 @@ impl Document {
         options.insert(Options::ENABLE_STRIKETHROUGH);
         options.insert(Options::ENABLE_TABLES);
 
-        let parser = Parser::new_ext(source, options);
+        let parser = Parser::new_ext(source, options).into_offset_iter();
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let line_of = |offset: usize| line_starts.partition_point(|&s| s <= offset) - 1;
+        let mut block_source_lines = Vec::new();
+        let mut block_start: Option<usize> = None;
         let mut blocks = Vec::new();
         let mut headings = Vec::new();
         let mut links = Vec::new();
         let mut current_line = 0;
         let mut current_link_url: Option<String> = None;
+        let mut heading_source_line = 0;
 
         // State for building blocks
         let mut current_spans: Vec<StyledSpan> = Vec::new();
@@ -189,13 +329,31 @@ impl Document {
         let mut list_items: Vec<ListItem> = Vec::new();
         let mut in_list_item = false;
 
-        for event in parser {
+        for (event, range) in parser {
+            // Remember where the next block starts in the source
+            if block_start.is_none()
+                && matches!(
+                    event,
+                    Event::Start(
+                        Tag::Heading { .. }
+                            | Tag::Paragraph
+                            | Tag::CodeBlock(_)
+                            | Tag::BlockQuote(_)
+                            | Tag::List(_)
+                    ) | Event::Rule
+                )
+            {
+                block_start = Some(range.start);
+            }
+
             match event {
                 Event::Start(tag) => match tag {
                     Tag::Heading { level, .. } => {
                         in_heading = Some(heading_level_to_u8(level));
+                        heading_source_line = line_of(range.start);
                         current_spans.clear();
                     }
+                    #[allow(clippy::collapsible_match)]
                     Tag::Paragraph => {
                         if !in_list_item {
                             in_paragraph = true;
@@ -239,6 +397,12 @@ impl Document {
                     Tag::Link { dest_url, .. } => {
                         current_style.link_url = Some(dest_url.to_string());
                         current_link_url = Some(dest_url.to_string());
+                        links.push(Link {
+                            url: dest_url.to_string(),
+                            text: String::new(),
+                            line_number: current_line,
+                            source_line: line_of(range.start),
+                        });
                     }
                     _ => {}
                 },
@@ -253,7 +417,9 @@ impl Document {
                             headings.push(Heading {
                                 level,
                                 text: text.clone(),
+                                slug: String::new(), // Set once all headings are known
                                 line_number: current_line,
+                                source_line: heading_source_line,
                                 rendered_line: 0, // Set during rendering
                             });
 
@@ -328,13 +494,11 @@ impl Document {
                     if in_code_block {
                         code_content.push_str(&text);
                     } else {
-                        // Track links
-                        if let Some(url) = &current_link_url {
-                            links.push(Link {
-                                url: url.clone(),
-                                text: text.to_string(),
-                                line_number: current_line,
-                            });
+                        // Collect link text
+                        if current_link_url.is_some()
+                            && let Some(link) = links.last_mut()
+                        {
+                            link.text.push_str(&text);
                         }
                         current_spans.push(StyledSpan {
                             text: text.to_string(),
@@ -343,6 +507,11 @@ impl Document {
                     }
                 }
                 Event::Code(code) => {
+                    if current_link_url.is_some()
+                        && let Some(link) = links.last_mut()
+                    {
+                        link.text.push_str(&code);
+                    }
                     let mut style = current_style.clone();
                     style.code = true;
                     current_spans.push(StyledSpan {
@@ -362,12 +531,27 @@ impl Document {
                 }
                 _ => {}
             }
+
+            // Blocks pushed by this event start at the remembered offset
+            while block_source_lines.len() < blocks.len() {
+                let offset = block_start.take().unwrap_or(range.start);
+                block_source_lines.push(line_of(offset));
+            }
+        }
+
+        let slugs = outline::slugs(&headings);
+        for (heading, slug) in headings.iter_mut().zip(slugs) {
+            heading.slug = slug;
         }
 
         Document {
             blocks,
             headings,
             links,
+            rendered_links: Vec::new(),
+            block_source_lines,
+            block_rendered_lines: Vec::new(),
+            unhighlighted_code: Vec::new(),
         }
     }
 
@@ -385,8 +569,11 @@ impl Document {
     ) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
         let mut heading_index = 0;
+        let mut rendered_links = Vec::new();
+        let mut block_rendered_lines = Vec::with_capacity(self.blocks.len());
 
         for block in &self.blocks {
+            block_rendered_lines.push(lines.len());
             match block {
                 Block::Heading { level, spans } => {
                     // Update the heading's rendered line position
@@ -412,6 +599,7 @@ impl Document {
                         line_spans.push(render_span(span, theme, Some(color)));
                     }
 
+                    record_links(&mut rendered_links, lines.len(), &line_spans, spans);
                     lines.push(Line::from(line_spans));
                     lines.push(Line::from("")); // blank line after heading
                 }
@@ -421,6 +609,7 @@ impl Document {
                     for span in spans {
                         line_spans.push(render_span(span, theme, None));
                     }
+                    record_links(&mut rendered_links, lines.len(), &line_spans, spans);
                     lines.push(Line::from(line_spans));
                     lines.push(Line::from("")); // blank line after paragraph
                 }
@@ -434,49 +623,12 @@ impl Document {
                     ));
 
                     // Code content with optional syntax highlighting
-                    if let Some(hl) = highlighter {
-                        let highlighted = hl.highlight(code, language.as_deref());
-                        for highlighted_line in highlighted {
-                            let mut line_spans =
-                                vec![Span::styled("  ", Style::default().bg(theme.code_block_bg))];
-                            for (style, text) in highlighted_line {
-                                let fg = syntect_to_ratatui_color(style.foreground);
-                                let mut ratatui_style =
-                                    Style::default().fg(fg).bg(theme.code_block_bg);
-                                if style
-                                    .font_style
-                                    .contains(syntect::highlighting::FontStyle::BOLD)
-                                {
-                                    ratatui_style = ratatui_style.add_modifier(Modifier::BOLD);
-                                }
-                                if style
-                                    .font_style
-                                    .contains(syntect::highlighting::FontStyle::ITALIC)
-                                {
-                                    ratatui_style = ratatui_style.add_modifier(Modifier::ITALIC);
-                                }
-                                if style
-                                    .font_style
-                                    .contains(syntect::highlighting::FontStyle::UNDERLINE)
-                                {
-                                    ratatui_style =
-                                        ratatui_style.add_modifier(Modifier::UNDERLINED);
-                                }
-                                line_spans.push(Span::styled(text, ratatui_style));
-                            }
-                            lines.push(Line::from(line_spans));
-                        }
-                    } else {
-                        // Fallback: no highlighting
-                        for code_line in code.lines() {
-                            lines.push(Line::styled(
-                                format!("  {}", code_line),
-                                Style::default()
-                                    .fg(theme.code_inline)
-                                    .bg(theme.code_block_bg),
-                            ));
-                        }
-                    }
+                    lines.extend(render_code_lines(
+                        code,
+                        language.as_deref(),
+                        theme,
+                        highlighter,
+                    ));
 
                     // Code fence end
                     lines.push(Line::styled("```", Style::default().fg(theme.text_muted)));
@@ -503,6 +655,7 @@ impl Document {
                             line_spans.push(render_span(span, theme, None));
                         }
 
+                        record_links(&mut rendered_links, lines.len(), &line_spans, &item.spans);
                         lines.push(Line::from(line_spans));
                     }
                     lines.push(Line::from("")); // blank line after list
@@ -513,14 +666,17 @@ impl Document {
                         vec![Span::styled("│ ", Style::default().fg(theme.blockquote))];
 
                     for span in spans {
-                        line_spans.push(Span::styled(
-                            span.text.clone(),
-                            Style::default()
-                                .fg(theme.blockquote)
-                                .add_modifier(Modifier::ITALIC),
-                        ));
+                        let mut style = Style::default()
+                            .fg(theme.blockquote)
+                            .add_modifier(Modifier::ITALIC);
+                        // Links stay recognizable (and clickable) in the quote color
+                        if span.style.link_url.is_some() {
+                            style = style.add_modifier(Modifier::UNDERLINED);
+                        }
+                        line_spans.push(Span::styled(span.text.clone(), style));
                     }
 
+                    record_links(&mut rendered_links, lines.len(), &line_spans, spans);
                     lines.push(Line::from(line_spans));
                     lines.push(Line::from("")); // blank line after blockquote
                 }
@@ -535,9 +691,73 @@ impl Document {
             }
         }
 
+        self.rendered_links = rendered_links;
+        self.block_rendered_lines = block_rendered_lines;
+        self.unhighlighted_code.clear();
         lines
     }
 
+    /// Render without syntax highlighting, leaving code blocks to be
+    /// highlighted later by [`Document::highlight_lines`] (e.g. as they scroll
+    /// into view). Highlighting doesn't change the layout, so the lines can be
+    /// shown straight away.
+    pub fn render_deferred(&mut self, theme: &Theme) -> Vec<Line<'static>> {
+        let lines = self.render_with_highlighting(theme, None);
+        self.unhighlighted_code = self
+            .blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| matches!(block, Block::CodeBlock { .. }))
+            .map(|(i, _)| i)
+            .collect();
+        lines
+    }
+
+    /// Highlight the deferred code blocks overlapping rendered lines `range`,
+    /// replacing their lines in `lines`. Returns whether any lines changed.
+    pub fn highlight_lines(
+        &mut self,
+        lines: &mut [Line<'static>],
+        range: std::ops::Range<usize>,
+        theme: &Theme,
+        highlighter: &SyntaxHighlighter,
+    ) -> bool {
+        let mut changed = false;
+
+        self.unhighlighted_code.retain(|&block| {
+            let Some(Block::CodeBlock { language, code }) = self.blocks.get(block) else {
+                return false;
+            };
+            // Content starts after the opening fence
+            let start = self.block_rendered_lines.get(block).map_or(0, |&l| l + 1);
+            let end = start + code.lines().count();
+            if end <= range.start || start >= range.end {
+                return true;
+            }
+
+            let highlighted =
+                render_code_lines(code, language.as_deref(), theme, Some(highlighter));
+            if let Some(target) = lines.get_mut(start..end) {
+                for (line, new) in target.iter_mut().zip(highlighted) {
+                    *line = new;
+                }
+                changed = true;
+            }
+            false
+        });
+
+        changed
+    }
+
+    /// Map a source line (0-based) to the rendered line of the block containing it
+    pub fn rendered_line_for_source(&self, source_line: usize) -> usize {
+        let block = self
+            .block_source_lines
+            .partition_point(|&line| line <= source_line)
+            .saturating_sub(1);
+        self.block_rendered_lines.get(block).copied().unwrap_or(0)
+    }
+
     /// Get total line count (estimated)
     #[allow(dead_code)]
     pub fn line_count(&mut self) -> usize {
@@ -548,6 +768,13 @@ impl Document {
     pub fn link_at_line(&self, line: usize) -> Option<&Link> {
         self.links.iter().find(|link| link.line_number == line)
     }
+
+    /// Get the rendered link at a line and column (if any)
+    pub fn link_at(&self, line: usize, column: usize) -> Option<&RenderedLink> {
+        self.rendered_links
+            .iter()
+            .find(|link| link.line == line && column >= link.start && column < link.end)
+    }
 }
 
 /// Convert heading level enum to u8
@@ -562,6 +789,39 @@ fn heading_level_to_u8(level: HeadingLevel) -> u8 {
     }
 }
 
+/// Record positions of link spans on a rendered line, for every block with
+/// inline text (headings, paragraphs, list items and block quotes). Tables are
+/// not rendered, so links in them can't be clicked.
+/// `line_spans` ends with the rendered form of `spans` (any prefix spans come first).
+fn record_links(
+    rendered_links: &mut Vec<RenderedLink>,
+    line: usize,
+    line_spans: &[Span<'static>],
+    spans: &[StyledSpan],
+) {
+    let prefix_len = line_spans.len() - spans.len();
+    let mut column: usize = line_spans[..prefix_len].iter().map(|s| s.width()).sum();
+
+    for (span, rendered) in spans.iter().zip(&line_spans[prefix_len..]) {
+        let width = rendered.width();
+        if let Some(url) = &span.style.link_url {
+            // Merge with the previous span if it's the same link (e.g. **bold** inside a link)
+            match rendered_links.last_mut() {
+                Some(last) if last.line == line && last.end == column && last.url == *url => {
+                    last.end += width;
+                }
+                _ => rendered_links.push(RenderedLink {
+                    url: url.clone(),
+                    line,
+                    start: column,
+                    end: column + width,
+                }),
+            }
+        }
+        column += width;
+    }
+}
+
 /// Render a styled span to a ratatui Span
 fn render_span(span: &StyledSpan, theme: &Theme, base_color: Option<Color>) -> Span<'static> {
     let mut style = Style::default();
@@ -597,3 +857,62 @@ fn render_span(span: &StyledSpan, theme: &Theme, base_color: Option<Color>) -> S
 
     Span::styled(span.text.clone(), style)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deferred_highlighting_matches_eager_render() {
+        let source =
+            "# Code\n\n```rust\nfn main() {}\n```\n\nText.\n\n```python\nprint(1)\nprint(2)\n```\n";
+        let theme = Theme::default();
+        let highlighter = SyntaxHighlighter::default();
+        let eager = Document::parse(source).render_with_highlighting(&theme, Some(&highlighter));
+
+        let mut document = Document::parse(source);
+        let mut lines = document.render_deferred(&theme);
+        assert_eq!(lines.len(), eager.len());
+        assert_eq!(document.unhighlighted_code, vec![1, 3]);
+
+        // Only the block overlapping the range is highlighted
+        assert!(document.highlight_lines(&mut lines, 0..4, &theme, &highlighter));
+        assert_eq!(document.unhighlighted_code, vec![3]);
+        assert_eq!(lines[3], eager[3]);
+        assert_ne!(lines[9], eager[9]);
+
+        assert!(document.highlight_lines(&mut lines, 0..usize::MAX, &theme, &highlighter));
+        assert!(!document.highlight_lines(&mut lines, 0..usize::MAX, &theme, &highlighter));
+        assert_eq!(lines, eager);
+    }
+
+    #[test]
+    fn test_json_lines_are_one_based() {
+        let document = Document::parse("# Title\n\nSee [x](http://e.com).\n\n---\n");
+        let json = serde_json::to_value(&document).unwrap();
+
+        let block_lines: Vec<_> = json["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|block| block["line"].as_u64().unwrap())
+            .collect();
+        assert_eq!(block_lines, vec![1, 3, 5]);
+        assert_eq!(json["blocks"][2]["type"], "horizontal_rule");
+        assert_eq!(json["headings"][0]["line"], 1);
+        assert_eq!(json["links"][0]["line"], 3);
+        assert!(json.get("block_source_lines").is_none());
+    }
+
+    #[test]
+    fn test_links_in_block_quotes_are_clickable() {
+        let mut document = Document::parse("> see [docs](http://d.com) here\n");
+        document.render(&Theme::default());
+
+        // Rendered as "│ see docs here"
+        assert!(document.link_at(0, 5).is_none());
+        assert_eq!(document.link_at(0, 6).unwrap().url, "http://d.com");
+        assert_eq!(document.link_at(0, 9).unwrap().url, "http://d.com");
+        assert!(document.link_at(0, 10).is_none());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::markdown::Heading;
+
+/// GitHub-style anchor for a heading: lowercase, punctuation removed,
+/// spaces turned into hyphens
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Unique anchors for all headings, numbering repeats (`usage`, `usage-1`, ...)
+pub fn slugs(headings: &[Heading]) -> Vec<String> {
+    let mut seen: Vec<String> = Vec::with_capacity(headings.len());
+
+    for heading in headings {
+        let base = slugify(&heading.text);
+        let mut slug = base.clone();
+        let mut n = 1;
+        while seen.contains(&slug) {
+            slug = format!("{}-{}", base, n);
+            n += 1;
+        }
+        seen.push(slug);
+    }
+
+    seen
+}
+
+/// Find a heading by exact text, then anchor (with or without `#`), then
+/// substring. Matching ignores case.
+pub fn find_heading(headings: &[Heading], name: &str) -> Option<usize> {
+    let name = name.to_lowercase();
+    let anchor = name.strip_prefix('#').unwrap_or(&name);
+
+    headings
+        .iter()
+        .position(|h| h.text.to_lowercase() == name)
+        .or_else(|| headings.iter().position(|h| h.slug == anchor))
+        .or_else(|| {
+            headings
+                .iter()
+                .position(|h| h.text.to_lowercase().contains(&name))
+        })
+}
+
+/// Source lines of a heading's section: from the heading up to the next
+/// heading of the same or a higher level
+pub fn section_lines(
+    headings: &[Heading],
+    index: usize,
+    total_lines: usize,
+) -> std::ops::Range<usize> {
+    let heading = &headings[index];
+    let end = headings[index + 1..]
+        .iter()
+        .find(|h| h.level <= heading.level)
+        .map_or(total_lines, |h| h.source_line);
+    heading.source_line..end.max(heading.source_line)
+}
+
+/// Markdown source of a heading's section, without trailing blank lines
+pub fn section_source(source: &str, headings: &[Heading], index: usize) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let range = section_lines(headings, index, lines.len());
+    let mut section = lines[range.start.min(lines.len())..range.end.min(lines.len())].join("\n");
+    section.truncate(section.trim_end().len());
+    section.push('\n');
+    section
+}
+
+/// Table of contents as a nested markdown list linking to each heading
+pub fn toc(headings: &[Heading]) -> String {
+    let min_level = headings.iter().map(|h| h.level).min().unwrap_or(1);
+    let mut toc = String::new();
+
+    for heading in headings {
+        let indent = "  ".repeat((heading.level - min_level) as usize);
+        toc.push_str(&format!(
+            "{}- [{}](#{})\n",
+            indent,
+            heading.text.trim(),
+            heading.slug
+        ));
+    }
+
+    toc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::Document;
+
+    const SOURCE: &str = "# Title\n\nIntro\n\n## Install\n\nRun it.\n\n### From source\n\nBuild.\n\n## Usage\n\nUse it.\n\n## Usage\n";
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started"), "getting-started");
+        assert_eq!(slugify("What's new in v1.2?"), "whats-new-in-v12");
+        assert_eq!(slugify("snake_case & more"), "snake_case--more");
+    }
+
+    #[test]
+    fn test_slugs_are_unique() {
+        let doc = Document::parse(SOURCE);
+        assert_eq!(
+            slugs(&doc.headings),
+            vec!["title", "install", "from-source", "usage", "usage-1"]
+        );
+        assert_eq!(doc.headings[4].slug, "usage-1");
+    }
+
+    #[test]
+    fn test_find_heading() {
+        let doc = Document::parse(SOURCE);
+        assert_eq!(find_heading(&doc.headings, "install"), Some(1));
+        assert_eq!(find_heading(&doc.headings, "#from-source"), Some(2));
+        assert_eq!(find_heading(&doc.headings, "usage-1"), Some(4));
+        assert_eq!(find_heading(&doc.headings, "source"), Some(2));
+        assert_eq!(find_heading(&doc.headings, "missing"), None);
+    }
+
+    #[test]
+    fn test_section_source() {
+        let doc = Document::parse(SOURCE);
+        assert_eq!(
+            section_source(SOURCE, &doc.headings, 1),
+            "## Install\n\nRun it.\n\n### From source\n\nBuild.\n"
+        );
+        assert_eq!(section_source(SOURCE, &doc.headings, 4), "## Usage\n");
+        assert_eq!(
+            section_source(SOURCE, &doc.headings, 0),
+            SOURCE.trim_end().to_string() + "\n"
+        );
+    }
+
+    #[test]
+    fn test_toc() {
+        let doc = Document::parse("## A\n\n### B\n\n## C\n");
+        assert_eq!(toc(&doc.headings), "- [A](#a)\n  - [B](#b)\n- [C](#c)\n");
+    }
+}
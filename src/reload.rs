@@ -0,0 +1,194 @@
+use barkdocs::markdown::Document;
+use ratatui::text::Line;
+use similar::{Algorithm, DiffOp};
+use std::hash::{DefaultHasher, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long lines changed by a reload stay highlighted
+pub const CHANGE_HIGHLIGHT: Duration = Duration::from_secs(3);
+
+/// Comparison of the rendered lines before and after a reload
+#[derive(Debug, Default, PartialEq)]
+pub struct LineDiff {
+    /// Indices of added or changed lines in the new rendering, ascending
+    pub changed: Vec<usize>,
+    /// Position in the new rendering of each old line, if it is unchanged
+    old_to_new: Vec<Option<usize>>,
+}
+
+impl LineDiff {
+    pub fn new(old: &[Line], new: &[Line]) -> Self {
+        let mut diff = Self {
+            changed: Vec::new(),
+            old_to_new: vec![None; old.len()],
+        };
+
+        let old_keys: Vec<u64> = old.iter().map(line_key).collect();
+        let new_keys: Vec<u64> = new.iter().map(line_key).collect();
+
+        for op in similar::capture_diff_slices(Algorithm::Patience, &old_keys, &new_keys) {
+            match op {
+                DiffOp::Equal {
+                    old_index,
+                    new_index,
+                    len,
+                } => {
+                    for i in 0..len {
+                        diff.old_to_new[old_index + i] = Some(new_index + i);
+                    }
+                }
+                DiffOp::Insert {
+                    new_index, new_len, ..
+                }
+                | DiffOp::Replace {
+                    new_index, new_len, ..
+                } => diff.changed.extend(new_index..new_index + new_len),
+                DiffOp::Delete { .. } => {}
+            }
+        }
+
+        diff
+    }
+
+    /// Where an unchanged old line ended up
+    pub fn map_line(&self, old_line: usize) -> Option<usize> {
+        self.old_to_new.get(old_line).copied().flatten()
+    }
+}
+
+/// Hash of a line's text, so lines compare cheaply when diffing. Styles are
+/// left out: code blocks are syntax highlighted lazily, so the same line can
+/// be styled differently before and after a reload.
+fn line_key(line: &Line) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for span in &line.spans {
+        hasher.write(span.content.as_bytes());
+    }
+    hasher.finish()
+}
+
+/// Scroll position in the reloaded document that keeps the same content at
+/// the top of the view. Tries, in order: the top line itself, the start of
+/// the block containing it, and the nearest heading above it (by anchor),
+/// keeping the offset into the block or section.
+pub fn anchor_scroll(old: &Document, new: &Document, diff: &LineDiff, scroll: usize) -> usize {
+    if let Some(line) = diff.map_line(scroll) {
+        return line;
+    }
+
+    let block_start = old
+        .block_rendered_lines
+        .iter()
+        .rev()
+        .find(|&&start| start <= scroll);
+    if let Some(&start) = block_start
+        && let Some(line) = diff.map_line(start)
+    {
+        return line + (scroll - start);
+    }
+
+    let heading = old
+        .headings
+        .iter()
+        .rev()
+        .find(|h| h.rendered_line <= scroll);
+    if let Some(heading) = heading
+        && let Some(found) = new.headings.iter().find(|h| h.slug == heading.slug)
+    {
+        return found.rendered_line + (scroll - heading.rendered_line);
+    }
+
+    scroll
+}
+
+/// Lines of a document highlighted after it changed on disk
+#[derive(Debug)]
+pub struct ChangeHighlight {
+    pub path: PathBuf,
+    pub lines: Vec<usize>,
+    pub since: Instant,
+}
+
+impl ChangeHighlight {
+    /// The highlighted lines, while the highlight lasts and `path` is shown
+    pub fn lines_for(&self, path: &Path) -> Option<&[usize]> {
+        (self.path == path && self.since.elapsed() < CHANGE_HIGHLIGHT).then_some(&self.lines[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use barkdocs::theme::Theme;
+    use ratatui::style::{Color, Style};
+    use ratatui::text::Span;
+
+    fn render(source: &str) -> (Document, Vec<Line<'static>>) {
+        let mut document = Document::parse(source);
+        let lines = document.render_with_highlighting(&Theme::default(), None);
+        (document, lines)
+    }
+
+    fn line_of(lines: &[Line], text: &str) -> usize {
+        lines
+            .iter()
+            .position(|l| l.spans.iter().any(|s| s.content.contains(text)))
+            .unwrap()
+    }
+
+    const BEFORE: &str =
+        "# Title\n\nIntro.\n\n## Install\n\nRun it.\n\nMore text.\n\n## Usage\n\nUse it.\n";
+
+    #[test]
+    fn test_diff_reports_changed_lines() {
+        let (_, old) = render(BEFORE);
+        let (_, new) = render(&BEFORE.replace("Run it.", "Run it now."));
+        let diff = LineDiff::new(&old, &new);
+        assert_eq!(diff.changed, vec![line_of(&new, "Run it now.")]);
+
+        let (_, same) = render(BEFORE);
+        assert!(LineDiff::new(&old, &same).changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_ignores_styling() {
+        let plain = vec![Line::from("  fn main() {}")];
+        let highlighted = vec![Line::from(vec![
+            Span::styled("  ", Style::default().bg(Color::Blue)),
+            Span::styled("fn", Style::default().fg(Color::Red)),
+            Span::raw(" main() {}"),
+        ])];
+        assert!(LineDiff::new(&plain, &highlighted).changed.is_empty());
+    }
+
+    #[test]
+    fn test_anchor_follows_unchanged_line() {
+        let (old_doc, old) = render(BEFORE);
+        let (new_doc, new) =
+            render(&BEFORE.replace("Intro.", "Intro.\n\nA new\nparagraph.\n\nAnd another."));
+        let diff = LineDiff::new(&old, &new);
+
+        let scroll = line_of(&old, "Usage");
+        assert_eq!(
+            anchor_scroll(&old_doc, &new_doc, &diff, scroll),
+            line_of(&new, "Usage")
+        );
+    }
+
+    #[test]
+    fn test_anchor_falls_back_to_heading() {
+        let (old_doc, old) = render(BEFORE);
+        // Rewrite the whole Install section except its heading
+        let edited = BEFORE.replace("Run it.\n\nMore text.", "Step one.\n\nStep two.");
+        let (new_doc, new) = render(&format!("Preface.\n\n{}", edited));
+        let diff = LineDiff::new(&old, &new);
+
+        let scroll = line_of(&old, "More text.");
+        let offset = scroll - line_of(&old, "Install");
+        assert_eq!(
+            anchor_scroll(&old_doc, &new_doc, &diff, scroll),
+            line_of(&new, "Install") + offset
+        );
+    }
+}
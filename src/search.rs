@@ -0,0 +1,373 @@
+use crate::markdown::{Document, Heading};
+use crate::theme::Theme;
+use ratatui::text::Line;
+use regex::{Regex, RegexBuilder};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Maximum characters of context shown in a result snippet
+const SNIPPET_CHARS: usize = 80;
+
+/// Search match location
+#[derive(Clone, Debug)]
+pub struct SearchMatch {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Options controlling how a query is matched
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression
+    pub regex: bool,
+    /// Only match whole words
+    pub whole_word: bool,
+}
+
+/// Build a regex for a search query.
+///
+/// Uses smart-case: matching is case-insensitive unless the query
+/// contains an uppercase character.
+pub fn build_pattern(query: &str, options: SearchOptions) -> Result<Regex, String> {
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+
+    let pattern = if options.whole_word {
+        format!(r"\b(?:{})\b", pattern)
+    } else {
+        pattern
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!has_uppercase(query, options.regex))
+        .build()
+        .map_err(|e| short_error(&e))
+}
+
+/// Check whether a query contains uppercase characters.
+/// In regex mode, escaped characters (e.g. `\S`, `\W`) are ignored.
+fn has_uppercase(query: &str, is_regex: bool) -> bool {
+    let mut chars = query.chars();
+    while let Some(c) = chars.next() {
+        if is_regex && c == '\\' {
+            chars.next();
+        } else if c.is_uppercase() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Condense a regex error into a single line for display
+fn short_error(error: &regex::Error) -> String {
+    let message = error.to_string();
+    message
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix("error: "))
+        .unwrap_or_else(|| message.lines().next().unwrap_or("invalid regex"))
+        .to_string()
+}
+
+/// Get the plain text of a rendered line
+pub fn line_text(line: &Line) -> String {
+    line.spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+/// Find all matches of a pattern in rendered lines
+pub fn find_matches(lines: &[Line], pattern: &Regex) -> Vec<SearchMatch> {
+    let mut matches = Vec::new();
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        let text = line_text(line);
+        for m in pattern.find_iter(&text) {
+            // Skip empty matches (e.g. `a*`), they can't be highlighted
+            if m.start() == m.end() {
+                continue;
+            }
+            matches.push(SearchMatch {
+                line: line_idx,
+                start: m.start(),
+                end: m.end(),
+            });
+        }
+    }
+
+    matches
+}
+
+/// Where a search hit was found
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HitSource {
+    /// Index into the open document buffers
+    Buffer(usize),
+    /// Markdown file found by a project search
+    File(PathBuf),
+}
+
+/// A search hit with context, for results overlays
+#[derive(Clone, Debug)]
+pub struct SearchHit {
+    pub source: HitSource,
+    /// Rendered line of the match
+    pub line: usize,
+    /// Byte offset of the match within the rendered line
+    pub start: usize,
+    /// Text of the enclosing heading (if any)
+    pub heading: Option<String>,
+    /// Line text around the match
+    pub snippet: String,
+    /// Byte range of the match within the snippet
+    pub snippet_start: usize,
+    pub snippet_end: usize,
+}
+
+/// Search a rendered document, returning hits with heading context
+pub fn search_document(
+    lines: &[Line],
+    headings: &[Heading],
+    pattern: &Regex,
+    source: HitSource,
+) -> Vec<SearchHit> {
+    find_matches(lines, pattern)
+        .into_iter()
+        .map(|m| {
+            let text = line_text(&lines[m.line]);
+            let (snippet, snippet_start, snippet_end) = make_snippet(&text, m.start, m.end);
+            SearchHit {
+                source: source.clone(),
+                line: m.line,
+                start: m.start,
+                heading: enclosing_heading(headings, m.line).map(|h| h.text.clone()),
+                snippet,
+                snippet_start,
+                snippet_end,
+            }
+        })
+        .collect()
+}
+
+/// Message streamed from a project search worker
+#[derive(Debug)]
+pub enum ProjectSearchMessage {
+    /// Hits found in one file
+    Hits(Vec<SearchHit>),
+    /// Search finished, with the number of files searched
+    Done(usize),
+}
+
+/// Search markdown files (e.g. every file under a project root), streaming
+/// hits per file.
+///
+/// Files are rendered the same way the viewer renders them (without syntax
+/// highlighting, which doesn't change line layout) so hit lines can be
+/// jumped to directly. Messages are passed to `send`, which returns false to
+/// stop; the search also stops early when `cancel` is set.
+pub fn search_project(
+    paths: impl IntoIterator<Item = PathBuf>,
+    pattern: &Regex,
+    theme: &Theme,
+    cancel: &Arc<AtomicBool>,
+    mut send: impl FnMut(ProjectSearchMessage) -> bool,
+) {
+    let mut searched = 0;
+
+    for path in paths {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        searched += 1;
+
+        let mut document = Document::parse(&content);
+        let lines = document.render_with_highlighting(theme, None);
+        let hits = search_document(&lines, &document.headings, pattern, HitSource::File(path));
+
+        if !hits.is_empty() && !send(ProjectSearchMessage::Hits(hits)) {
+            return;
+        }
+    }
+
+    send(ProjectSearchMessage::Done(searched));
+}
+
+/// Find the last heading at or above a rendered line
+pub fn enclosing_heading(headings: &[Heading], line: usize) -> Option<&Heading> {
+    headings.iter().rev().find(|h| h.rendered_line <= line)
+}
+
+/// Cut a snippet of at most `SNIPPET_CHARS` characters around a match,
+/// returning the snippet and the match range within it
+fn make_snippet(text: &str, start: usize, end: usize) -> (String, usize, usize) {
+    let char_count = text.chars().count();
+    if char_count <= SNIPPET_CHARS {
+        let trimmed = text.trim_start();
+        let offset = text.len() - trimmed.len();
+        let trimmed = trimmed.trim_end();
+        let start = start.saturating_sub(offset).min(trimmed.len());
+        let end = end.saturating_sub(offset).min(trimmed.len());
+        return (trimmed.to_string(), start, end);
+    }
+
+    // Keep some context before the match, then fill up to the limit
+    let match_start_char = text[..start].chars().count();
+    let first_char = match_start_char.saturating_sub(SNIPPET_CHARS / 4);
+    let byte_start = text
+        .char_indices()
+        .nth(first_char)
+        .map_or(text.len(), |(i, _)| i);
+    let byte_end = text
+        .char_indices()
+        .nth(first_char + SNIPPET_CHARS)
+        .map_or(text.len(), |(i, _)| i);
+
+    let snippet = text[byte_start..byte_end].to_string();
+    let start = start - byte_start;
+    let end = end.min(byte_end) - byte_start;
+    (snippet, start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(texts: &[&'static str]) -> Vec<Line<'static>> {
+        texts.iter().map(|t| Line::from(*t)).collect()
+    }
+
+    #[test]
+    fn test_smart_case_lowercase_is_insensitive() {
+        let re = build_pattern("hello", SearchOptions::default()).unwrap();
+        assert!(re.is_match("Hello World"));
+        assert!(re.is_match("HELLO"));
+    }
+
+    #[test]
+    fn test_smart_case_uppercase_is_sensitive() {
+        let re = build_pattern("Hello", SearchOptions::default()).unwrap();
+        assert!(re.is_match("Hello World"));
+        assert!(!re.is_match("hello world"));
+    }
+
+    #[test]
+    fn test_smart_case_ignores_regex_escapes() {
+        let options = SearchOptions {
+            regex: true,
+            whole_word: false,
+        };
+        let re = build_pattern(r"foo\S+", options).unwrap();
+        assert!(re.is_match("FOObar"));
+    }
+
+    #[test]
+    fn test_literal_escapes_regex_chars() {
+        let re = build_pattern("a.b", SearchOptions::default()).unwrap();
+        assert!(re.is_match("a.b"));
+        assert!(!re.is_match("axb"));
+    }
+
+    #[test]
+    fn test_whole_word() {
+        let options = SearchOptions {
+            regex: false,
+            whole_word: true,
+        };
+        let re = build_pattern("cat", options).unwrap();
+        assert!(re.is_match("the cat sat"));
+        assert!(!re.is_match("concatenate"));
+    }
+
+    #[test]
+    fn test_invalid_regex_error_is_single_line() {
+        let options = SearchOptions {
+            regex: true,
+            whole_word: false,
+        };
+        let err = build_pattern("(unclosed", options).unwrap_err();
+        assert!(!err.is_empty());
+        assert!(!err.contains('\n'));
+    }
+
+    #[test]
+    fn test_find_matches_positions() {
+        let lines = lines(&["one two one", "three", "one"]);
+        let re = build_pattern("one", SearchOptions::default()).unwrap();
+        let matches = find_matches(&lines, &re);
+
+        assert_eq!(matches.len(), 3);
+        assert_eq!(
+            (matches[0].line, matches[0].start, matches[0].end),
+            (0, 0, 3)
+        );
+        assert_eq!(
+            (matches[1].line, matches[1].start, matches[1].end),
+            (0, 8, 11)
+        );
+        assert_eq!(matches[2].line, 2);
+    }
+
+    #[test]
+    fn test_search_document_heading_context() {
+        let lines = lines(&["# Intro", "", "alpha", "## Usage", "", "alpha beta"]);
+        let headings = vec![
+            Heading {
+                level: 1,
+                text: "Intro".to_string(),
+                slug: "intro".to_string(),
+                line_number: 0,
+                source_line: 0,
+                rendered_line: 0,
+            },
+            Heading {
+                level: 2,
+                text: "Usage".to_string(),
+                slug: "usage".to_string(),
+                line_number: 1,
+                source_line: 2,
+                rendered_line: 3,
+            },
+        ];
+        let re = build_pattern("alpha", SearchOptions::default()).unwrap();
+        let hits = search_document(&lines, &headings, &re, HitSource::Buffer(2));
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].heading.as_deref(), Some("Intro"));
+        assert_eq!(hits[1].heading.as_deref(), Some("Usage"));
+        assert_eq!(hits[1].source, HitSource::Buffer(2));
+        assert_eq!(hits[1].snippet, "alpha beta");
+    }
+
+    #[test]
+    fn test_snippet_trims_whitespace() {
+        let (snippet, start, end) = make_snippet("    let x = 1;", 8, 9);
+        assert_eq!(snippet, "let x = 1;");
+        assert_eq!(&snippet[start..end], "x");
+    }
+
+    #[test]
+    fn test_snippet_long_line_keeps_match() {
+        let text = format!("{}needle{}", "a".repeat(200), "b".repeat(200));
+        let (snippet, start, end) = make_snippet(&text, 200, 206);
+        assert!(snippet.chars().count() <= SNIPPET_CHARS);
+        assert_eq!(&snippet[start..end], "needle");
+    }
+
+    #[test]
+    fn test_find_matches_skips_empty() {
+        let lines = lines(&["abc"]);
+        let options = SearchOptions {
+            regex: true,
+            whole_word: false,
+        };
+        let re = build_pattern("x*", options).unwrap();
+        assert!(find_matches(&lines, &re).is_empty());
+    }
+}
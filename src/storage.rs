@@ -101,6 +101,7 @@ impl History {
         }
 
         // Sort by most recent first
+        #[allow(clippy::unnecessary_sort_by)]
         self.entries
             .sort_by(|a, b| b.last_accessed.cmp(&a.last_accessed));
 
@@ -219,6 +220,94 @@ impl Bookmarks {
     }
 }
 
+/// A search history entry
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHistoryEntry {
+    /// Search query
+    pub query: String,
+    /// Whether the query was a regex
+    pub is_regex: bool,
+}
+
+/// Search history manager (most recent first)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SearchHistory {
+    entries: Vec<SearchHistoryEntry>,
+    #[serde(default = "default_max_entries")]
+    max_entries: usize,
+}
+
+impl SearchHistory {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            max_entries: 100,
+        }
+    }
+
+    /// Load search history from disk
+    pub fn load() -> Self {
+        let Some(path) = Self::storage_path() else {
+            return Self::new();
+        };
+
+        if !path.exists() {
+            return Self::new();
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|_| Self::new()),
+            Err(_) => Self::new(),
+        }
+    }
+
+    /// Save search history to disk
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let Some(path) = Self::storage_path() else {
+            return Ok(());
+        };
+
+        // Ensure parent directory exists
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)
+    }
+
+    /// Add a query, moving it to the front if it already exists
+    pub fn add(&mut self, query: &str, is_regex: bool) {
+        if query.is_empty() {
+            return;
+        }
+
+        self.entries.retain(|e| e.query != query);
+        self.entries.insert(
+            0,
+            SearchHistoryEntry {
+                query: query.to_string(),
+                is_regex,
+            },
+        );
+
+        // Trim to max entries
+        if self.entries.len() > self.max_entries {
+            self.entries.truncate(self.max_entries);
+        }
+    }
+
+    /// Get all entries (most recent first)
+    pub fn entries(&self) -> &[SearchHistoryEntry] {
+        &self.entries
+    }
+
+    /// Get storage path
+    fn storage_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|d| d.join("barkdocs").join("search_history.json"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +380,7 @@ mod tests {
         });
 
         // Sort by most recent (like add() does)
+        #[allow(clippy::unnecessary_sort_by)]
         history
             .entries
             .sort_by(|a, b| b.last_accessed.cmp(&a.last_accessed));
@@ -333,6 +423,7 @@ mod tests {
         });
 
         // Sort and truncate (like add() does)
+        #[allow(clippy::unnecessary_sort_by)]
         history
             .entries
             .sort_by(|a, b| b.last_accessed.cmp(&a.last_accessed));
@@ -466,4 +557,63 @@ mod tests {
         assert_eq!(deserialized.name, bookmark.name);
         assert_eq!(deserialized.created_at, bookmark.created_at);
     }
+
+    #[test]
+    fn test_search_history_add() {
+        let mut history = SearchHistory::new();
+
+        history.add("foo", false);
+        history.add("ba[rz]", true);
+
+        assert_eq!(history.entries().len(), 2);
+        assert_eq!(history.entries()[0].query, "ba[rz]");
+        assert!(history.entries()[0].is_regex);
+        assert_eq!(history.entries()[1].query, "foo");
+    }
+
+    #[test]
+    fn test_search_history_dedup_moves_to_front() {
+        let mut history = SearchHistory::new();
+
+        history.add("foo", false);
+        history.add("bar", false);
+        history.add("foo", true);
+
+        assert_eq!(history.entries().len(), 2);
+        assert_eq!(history.entries()[0].query, "foo");
+        assert!(history.entries()[0].is_regex);
+        assert_eq!(history.entries()[1].query, "bar");
+    }
+
+    #[test]
+    fn test_search_history_ignores_empty() {
+        let mut history = SearchHistory::new();
+        history.add("", false);
+        assert!(history.entries().is_empty());
+    }
+
+    #[test]
+    fn test_search_history_max_entries() {
+        let mut history = SearchHistory::new();
+        history.max_entries = 2;
+
+        history.add("one", false);
+        history.add("two", false);
+        history.add("three", false);
+
+        assert_eq!(history.entries().len(), 2);
+        assert_eq!(history.entries()[0].query, "three");
+        assert_eq!(history.entries()[1].query, "two");
+    }
+
+    #[test]
+    fn test_search_history_serialization() {
+        let mut history = SearchHistory::new();
+        history.add("needle", true);
+
+        let json = serde_json::to_string(&history).unwrap();
+        let deserialized: SearchHistory = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.entries(), history.entries());
+    }
 }
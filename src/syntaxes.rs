@@ -0,0 +1,237 @@
+use barkdocs::markdown::SyntaxHighlighter;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+
+/// Directory for custom `.sublime-syntax` files
+pub fn syntaxes_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("barkdocs").join("syntaxes"))
+}
+
+/// Directory for custom `.tmTheme` files
+pub fn themes_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("barkdocs").join("themes"))
+}
+
+/// Directory for the compiled syntax set
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("barkdocs"))
+}
+
+/// Load the syntax highlighter: the default syntaxes and themes plus the
+/// custom ones from the config directory. If custom files fail to load, the
+/// defaults are used and the error is returned alongside.
+pub fn load_highlighter() -> (SyntaxHighlighter, Option<String>) {
+    let mut error = None;
+
+    let syntax_set = match syntaxes_dir() {
+        Some(dir) => load_syntax_set(&dir, cache_dir().as_deref()).unwrap_or_else(|e| {
+            error = Some(format!("Custom syntaxes not loaded: {}", e));
+            SyntaxSet::load_defaults_newlines()
+        }),
+        None => SyntaxSet::load_defaults_newlines(),
+    };
+
+    let theme_set = match themes_dir() {
+        Some(dir) => load_theme_set(&dir).unwrap_or_else(|e| {
+            error = Some(format!("Custom themes not loaded: {}", e));
+            ThemeSet::load_defaults()
+        }),
+        None => ThemeSet::load_defaults(),
+    };
+
+    let highlighter = SyntaxHighlighter::from_sets(syntax_set, theme_set, "base16-ocean.dark");
+    (highlighter, error)
+}
+
+/// The default syntaxes plus the `.sublime-syntax` files under `dir`.
+///
+/// Building the combined set takes a while, so it is dumped to `cache_dir`
+/// and reused until the custom files change.
+pub fn load_syntax_set(dir: &Path, cache_dir: Option<&Path>) -> Result<SyntaxSet, String> {
+    let files = custom_files(dir, "sublime-syntax");
+    if files.is_empty() {
+        return Ok(SyntaxSet::load_defaults_newlines());
+    }
+
+    let dump_name = format!("syntaxes-{:016x}.packdump", fingerprint(&files));
+    let cache = cache_dir.map(|cache_dir| cache_dir.join(&dump_name));
+    if let Some(cache) = &cache
+        && let Ok(syntax_set) = syntect::dumps::from_uncompressed_dump_file(cache)
+    {
+        return Ok(syntax_set);
+    }
+
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+    builder
+        .add_from_folder(dir, true)
+        .map_err(|e| e.to_string())?;
+    let syntax_set = builder.build();
+
+    if let (Some(cache_dir), Some(cache)) = (cache_dir, &cache) {
+        remove_stale_dumps(cache_dir);
+        let _ = std::fs::create_dir_all(cache_dir);
+        let _ = syntect::dumps::dump_to_uncompressed_file(&syntax_set, cache);
+    }
+
+    Ok(syntax_set)
+}
+
+/// The default themes plus the `.tmTheme` files in `dir`, named after their
+/// file names
+pub fn load_theme_set(dir: &Path) -> Result<ThemeSet, String> {
+    let mut theme_set = ThemeSet::load_defaults();
+    if dir.is_dir() {
+        theme_set.add_from_folder(dir).map_err(|e| e.to_string())?;
+    }
+    Ok(theme_set)
+}
+
+/// Files with the given extension under `dir` (recursively), sorted
+fn custom_files(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == extension) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Hash of the files' paths, sizes and modification times, plus the barkdocs
+/// version (whose syntect may dump differently)
+fn fingerprint(files: &[PathBuf]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    for path in files {
+        path.hash(&mut hasher);
+        if let Ok(metadata) = std::fs::metadata(path) {
+            metadata.len().hash(&mut hasher);
+            metadata.modified().ok().hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Remove syntax sets dumped for earlier versions of the custom files
+fn remove_stale_dumps(cache_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with("syntaxes-") && name.ends_with(".packdump") {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYNTAX: &str = "%YAML 1.2
+---
+name: Barkconf
+file_extensions: [barkconf]
+scope: source.barkconf
+contexts:
+  main:
+    - match: '#.*'
+      scope: comment.line.barkconf
+";
+
+    const THEME: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>Bark</string>
+    <key>settings</key>
+    <array>
+        <dict>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#EEEEEE</string>
+                <key>background</key>
+                <string>#111111</string>
+            </dict>
+        </dict>
+    </array>
+</dict>
+</plist>
+"#;
+
+    fn dumps(cache_dir: &Path) -> Vec<PathBuf> {
+        let mut dumps: Vec<PathBuf> = std::fs::read_dir(cache_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        dumps.sort();
+        dumps
+    }
+
+    #[test]
+    fn test_custom_syntax_is_loaded_and_cached() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("syntaxes");
+        let cache_dir = root.path().join("cache");
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("nested").join("barkconf.sublime-syntax"), SYNTAX).unwrap();
+
+        let syntax_set = load_syntax_set(&dir, Some(&cache_dir)).unwrap();
+        assert!(syntax_set.find_syntax_by_token("barkconf").is_some());
+        assert!(syntax_set.find_syntax_by_token("rust").is_some());
+        let first = dumps(&cache_dir);
+        assert_eq!(first.len(), 1);
+
+        // Served from the dump while the files are unchanged
+        let cached = load_syntax_set(&dir, Some(&cache_dir)).unwrap();
+        assert!(cached.find_syntax_by_token("barkconf").is_some());
+        assert_eq!(dumps(&cache_dir), first);
+
+        // A changed file replaces the dump
+        std::fs::write(
+            dir.join("nested").join("barkconf.sublime-syntax"),
+            SYNTAX.replace("'#.*'", "';.*'"),
+        )
+        .unwrap();
+        load_syntax_set(&dir, Some(&cache_dir)).unwrap();
+        let second = dumps(&cache_dir);
+        assert_eq!(second.len(), 1);
+        assert_ne!(second, first);
+    }
+
+    #[test]
+    fn test_invalid_syntax_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bad.sublime-syntax"), "name: [unclosed").unwrap();
+        assert!(load_syntax_set(dir.path(), None).is_err());
+    }
+
+    #[test]
+    fn test_custom_theme_is_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bark.tmTheme"), THEME).unwrap();
+
+        let theme_set = load_theme_set(dir.path()).unwrap();
+        assert!(theme_set.themes.contains_key("bark"));
+        assert!(theme_set.themes.contains_key("base16-ocean.dark"));
+
+        let missing = load_theme_set(&dir.path().join("missing")).unwrap();
+        assert!(!missing.themes.contains_key("bark"));
+    }
+}
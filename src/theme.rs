@@ -24,6 +24,9 @@ pub struct Theme {
     pub highlight_match_bg: Color,
     pub highlight_match_fg: Color,
 
+    // Lines changed by the last reload
+    pub changed_line_bg: Color,
+
     // Markdown elements
     pub heading_1: Color,
     pub heading_2: Color,
@@ -39,6 +42,9 @@ pub struct Theme {
     pub list_marker: Color,
     pub horizontal_rule: Color,
 
+    // Syntect theme for code blocks, one of the defaults
+    pub syntax_theme: &'static str,
+
     // Outline panel
     pub outline_selected: Color,
     pub outline_heading: Color,
@@ -112,6 +118,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(250, 204, 21), // golden yellow
             highlight_match_fg: Color::Rgb(26, 32, 44),   // dark navy
+            changed_line_bg: Color::Rgb(28, 60, 48),      // deep green
 
             heading_1: Color::Rgb(246, 135, 179),     // pink
             heading_2: Color::Rgb(129, 230, 217),     // teal
@@ -126,6 +133,8 @@ impl Theme {
             list_marker: Color::Rgb(129, 230, 217),   // teal
             horizontal_rule: Color::Rgb(74, 85, 104), // muted slate
 
+            syntax_theme: "base16-ocean.dark",
+
             outline_selected: Color::Rgb(250, 204, 21), // golden
             outline_heading: Color::Rgb(226, 232, 240), // light gray
             outline_current: Color::Rgb(129, 230, 217), // teal
@@ -160,6 +169,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(255, 184, 108), // orange
             highlight_match_fg: Color::Rgb(40, 42, 54),
+            changed_line_bg: Color::Rgb(45, 74, 60),
 
             heading_1: Color::Rgb(255, 121, 198),    // pink
             heading_2: Color::Rgb(189, 147, 249),    // purple
@@ -174,6 +184,8 @@ impl Theme {
             list_marker: Color::Rgb(255, 121, 198),
             horizontal_rule: Color::Rgb(68, 71, 90),
 
+            syntax_theme: "base16-eighties.dark",
+
             outline_selected: Color::Rgb(255, 184, 108),
             outline_heading: Color::Rgb(248, 248, 242),
             outline_current: Color::Rgb(189, 147, 249),
@@ -208,6 +220,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(254, 128, 25), // orange
             highlight_match_fg: Color::Rgb(40, 40, 40),
+            changed_line_bg: Color::Rgb(50, 61, 30),
 
             heading_1: Color::Rgb(251, 73, 52),       // red
             heading_2: Color::Rgb(215, 153, 33),      // yellow
@@ -222,6 +235,8 @@ impl Theme {
             list_marker: Color::Rgb(215, 153, 33),
             horizontal_rule: Color::Rgb(102, 92, 84),
 
+            syntax_theme: "base16-mocha.dark",
+
             outline_selected: Color::Rgb(254, 128, 25),
             outline_heading: Color::Rgb(235, 219, 178),
             outline_current: Color::Rgb(215, 153, 33),
@@ -256,6 +271,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(208, 135, 112), // orange
             highlight_match_fg: Color::Rgb(46, 52, 64),
+            changed_line_bg: Color::Rgb(52, 68, 60),
 
             heading_1: Color::Rgb(191, 97, 106),      // red
             heading_2: Color::Rgb(208, 135, 112),     // orange
@@ -270,6 +286,8 @@ impl Theme {
             list_marker: Color::Rgb(136, 192, 208),
             horizontal_rule: Color::Rgb(76, 86, 106),
 
+            syntax_theme: "base16-ocean.dark",
+
             outline_selected: Color::Rgb(235, 203, 139),
             outline_heading: Color::Rgb(236, 239, 244),
             outline_current: Color::Rgb(136, 192, 208),
@@ -304,6 +322,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(181, 137, 0), // yellow
             highlight_match_fg: Color::Rgb(0, 43, 54),
+            changed_line_bg: Color::Rgb(10, 60, 50),
 
             heading_1: Color::Rgb(220, 50, 47),     // red
             heading_2: Color::Rgb(203, 75, 22),     // orange
@@ -318,6 +337,8 @@ impl Theme {
             list_marker: Color::Rgb(42, 161, 152),
             horizontal_rule: Color::Rgb(88, 110, 117),
 
+            syntax_theme: "Solarized (dark)",
+
             outline_selected: Color::Rgb(181, 137, 0),
             outline_heading: Color::Rgb(147, 161, 161),
             outline_current: Color::Rgb(38, 139, 210),
@@ -352,6 +373,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(181, 137, 0),
             highlight_match_fg: Color::Rgb(253, 246, 227),
+            changed_line_bg: Color::Rgb(226, 236, 200),
 
             heading_1: Color::Rgb(220, 50, 47),
             heading_2: Color::Rgb(203, 75, 22),
@@ -366,6 +388,8 @@ impl Theme {
             list_marker: Color::Rgb(42, 161, 152),
             horizontal_rule: Color::Rgb(147, 161, 161),
 
+            syntax_theme: "Solarized (light)",
+
             outline_selected: Color::Rgb(181, 137, 0),
             outline_heading: Color::Rgb(88, 110, 117),
             outline_current: Color::Rgb(38, 139, 210),
@@ -400,6 +424,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(253, 151, 31), // orange
             highlight_match_fg: Color::Rgb(39, 40, 34),
+            changed_line_bg: Color::Rgb(50, 66, 30),
 
             heading_1: Color::Rgb(249, 38, 114),
             heading_2: Color::Rgb(253, 151, 31),
@@ -414,6 +439,8 @@ impl Theme {
             list_marker: Color::Rgb(249, 38, 114),
             horizontal_rule: Color::Rgb(117, 113, 94),
 
+            syntax_theme: "base16-eighties.dark",
+
             outline_selected: Color::Rgb(253, 151, 31),
             outline_heading: Color::Rgb(248, 248, 242),
             outline_current: Color::Rgb(249, 38, 114),
@@ -448,6 +475,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(250, 179, 135), // peach
             highlight_match_fg: Color::Rgb(30, 30, 46),
+            changed_line_bg: Color::Rgb(44, 62, 56),
 
             heading_1: Color::Rgb(243, 139, 168),     // pink
             heading_2: Color::Rgb(203, 166, 247),     // mauve
@@ -462,6 +490,8 @@ impl Theme {
             list_marker: Color::Rgb(243, 139, 168),
             horizontal_rule: Color::Rgb(88, 91, 112),
 
+            syntax_theme: "base16-mocha.dark",
+
             outline_selected: Color::Rgb(250, 179, 135),
             outline_heading: Color::Rgb(205, 214, 244),
             outline_current: Color::Rgb(203, 166, 247),
@@ -496,6 +526,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(255, 158, 100), // orange
             highlight_match_fg: Color::Rgb(26, 27, 38),
+            changed_line_bg: Color::Rgb(32, 52, 48),
 
             heading_1: Color::Rgb(247, 118, 142),     // red
             heading_2: Color::Rgb(187, 154, 247),     // purple
@@ -510,6 +541,8 @@ impl Theme {
             list_marker: Color::Rgb(125, 207, 255),
             horizontal_rule: Color::Rgb(65, 72, 104),
 
+            syntax_theme: "base16-ocean.dark",
+
             outline_selected: Color::Rgb(255, 158, 100),
             outline_heading: Color::Rgb(192, 202, 245),
             outline_current: Color::Rgb(122, 162, 247),
@@ -544,6 +577,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(209, 154, 102), // orange
             highlight_match_fg: Color::Rgb(40, 44, 52),
+            changed_line_bg: Color::Rgb(45, 62, 50),
 
             heading_1: Color::Rgb(224, 108, 117),     // red
             heading_2: Color::Rgb(198, 120, 221),     // purple
@@ -558,6 +592,8 @@ impl Theme {
             list_marker: Color::Rgb(86, 182, 194),
             horizontal_rule: Color::Rgb(92, 99, 112),
 
+            syntax_theme: "base16-ocean.dark",
+
             outline_selected: Color::Rgb(209, 154, 102),
             outline_heading: Color::Rgb(171, 178, 191),
             outline_current: Color::Rgb(97, 175, 239),
@@ -592,6 +628,7 @@ impl Theme {
 
             highlight_match_bg: Color::Rgb(0, 255, 0),
             highlight_match_fg: Color::Black,
+            changed_line_bg: Color::Rgb(0, 60, 0),
 
             heading_1: Color::Rgb(0, 255, 0),
             heading_2: Color::Rgb(0, 220, 0),
@@ -606,6 +643,8 @@ impl Theme {
             list_marker: Color::Rgb(0, 255, 0),
             horizontal_rule: Color::Rgb(0, 80, 0),
 
+            syntax_theme: "base16-ocean.dark",
+
             outline_selected: Color::Rgb(0, 255, 0),
             outline_heading: Color::Rgb(0, 200, 0),
             outline_current: Color::Rgb(0, 255, 0),
@@ -726,6 +765,20 @@ mod tests {
         assert_eq!(theme.border_focused, Color::Rgb(0, 255, 0));
     }
 
+    #[test]
+    fn test_syntax_themes_exist() {
+        let syntax_themes = syntect::highlighting::ThemeSet::load_defaults();
+        for name in Theme::available_themes() {
+            let theme = Theme::by_name(name);
+            assert!(
+                syntax_themes.themes.contains_key(theme.syntax_theme),
+                "{} pairs with unknown syntax theme {}",
+                name,
+                theme.syntax_theme
+            );
+        }
+    }
+
     #[test]
     fn test_solarized_light_vs_dark() {
         let dark = Theme::solarized_dark();
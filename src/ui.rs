@@ -1,20 +1,21 @@
-use crate::app::{AppState, FocusedPanel, InputMode, SplitDirection};
-use crate::theme::Theme;
+use crate::app::{AppState, FocusedPanel, InputMode, SearchScope, SplitDirection};
+use barkdocs::theme::Theme;
+use barkdocs::view::MarkdownView;
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{
-    Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
-    ScrollbarState, Wrap,
-};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
+
+/// Width of list overlays that include a preview pane
+const PREVIEW_OVERLAY_WIDTH: u16 = 110;
 
 /// Main draw function
 pub fn draw(frame: &mut Frame, state: &mut AppState) {
     let area = frame.area();
 
-    // Layout: [Optional Outline Panel] [Main Content]
-    let main_chunks = if state.show_outline {
+    // Layout: [Optional Sidebar (file tree / outline)] [Main Content]
+    let main_chunks = if state.sidebar_visible() {
         Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Length(state.outline_width), Constraint::Min(20)])
@@ -26,15 +27,34 @@ pub fn draw(frame: &mut Frame, state: &mut AppState) {
             .split(area)
     };
 
-    let (outline_area, content_area) = if state.show_outline {
+    let (sidebar_area, content_area) = if state.sidebar_visible() {
         (main_chunks[0], main_chunks[1])
     } else {
         (Rect::default(), main_chunks[0])
     };
 
-    // Store outline area for mouse handling
+    // File tree stacks above the outline when both are shown
+    let (file_tree_area, outline_area) = match (state.show_file_tree, state.show_outline) {
+        (true, true) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(sidebar_area);
+            (chunks[0], chunks[1])
+        }
+        (true, false) => (sidebar_area, Rect::default()),
+        (false, true) => (Rect::default(), sidebar_area),
+        (false, false) => (Rect::default(), Rect::default()),
+    };
+
+    // Store panel areas for mouse handling
+    state.file_tree_area = file_tree_area;
     state.outline_area = outline_area;
 
+    if state.show_file_tree {
+        draw_file_tree(frame, state, file_tree_area);
+    }
+
     if state.show_outline {
         draw_outline(frame, state, outline_area);
     }
@@ -64,6 +84,10 @@ pub fn draw(frame: &mut Frame, state: &mut AppState) {
         draw_settings_overlay(frame, state);
     }
 
+    if state.show_syntax_theme_picker {
+        draw_syntax_theme_picker(frame, state);
+    }
+
     if state.show_file_picker {
         draw_file_picker(frame, state);
     }
@@ -87,6 +111,10 @@ pub fn draw(frame: &mut Frame, state: &mut AppState) {
     if state.show_bookmark_name_input {
         draw_bookmark_name_input(frame, state);
     }
+
+    if state.show_global_search {
+        draw_global_search(frame, state);
+    }
 }
 
 /// Draw the header bar
@@ -182,6 +210,83 @@ fn draw_outline(frame: &mut Frame, state: &AppState, area: Rect) {
     }
 }
 
+/// Draw the file tree panel
+fn draw_file_tree(frame: &mut Frame, state: &mut AppState, area: Rect) {
+    let focused = state.focused_panel == FocusedPanel::FileTree;
+
+    let border_color = if focused {
+        state.theme.border_focused
+    } else {
+        state.theme.border_unfocused
+    };
+
+    let block = Block::default()
+        .title(" Files ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let visible = state.file_tree.visible();
+    if visible.is_empty() {
+        let empty = Paragraph::new("(no markdown files)")
+            .style(Style::default().fg(state.theme.empty_state));
+        frame.render_widget(empty, inner);
+        return;
+    }
+
+    // Keep the selection in view
+    let height = (inner.height as usize).max(1);
+    let selected = state.file_tree_selected.min(visible.len() - 1);
+    let mut scroll = state.file_tree_scroll.min(visible.len().saturating_sub(1));
+    if selected < scroll {
+        scroll = selected;
+    } else if selected >= scroll + height {
+        scroll = selected + 1 - height;
+    }
+
+    let current = state.current_file_canonical();
+    let theme = &state.theme;
+
+    let items: Vec<ListItem> = visible
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(height)
+        .map(|(i, entry)| {
+            let marker = if i == selected { "> " } else { "  " };
+            let indent = "  ".repeat(entry.depth);
+            let icon = match (entry.is_dir, state.file_tree.is_expanded(&entry.path)) {
+                (true, true) => "▾ ",
+                (true, false) => "▸ ",
+                (false, _) => "  ",
+            };
+            let is_current = current.as_ref() == Some(&entry.path);
+
+            let mut style = if i == selected {
+                Style::default()
+                    .fg(theme.outline_selected)
+                    .add_modifier(Modifier::BOLD)
+            } else if is_current {
+                Style::default().fg(theme.outline_current)
+            } else if entry.is_dir {
+                Style::default().fg(theme.link)
+            } else {
+                Style::default().fg(theme.outline_heading)
+            };
+            if is_current {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+
+            ListItem::new(format!("{}{}{}{}", marker, indent, icon, entry.name)).style(style)
+        })
+        .collect();
+
+    frame.render_widget(List::new(items), inner);
+    state.file_tree_scroll = scroll;
+}
+
 /// Draw the main content area
 fn draw_content(frame: &mut Frame, state: &mut AppState, area: Rect) {
     // Handle split view
@@ -194,7 +299,10 @@ fn draw_content(frame: &mut Frame, state: &mut AppState, area: Rect) {
 
         Layout::default()
             .direction(direction)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .constraints([
+                Constraint::Percentage(state.split_ratio),
+                Constraint::Percentage(100 - state.split_ratio),
+            ])
             .split(area)
             .to_vec()
     } else {
@@ -204,224 +312,53 @@ fn draw_content(frame: &mut Frame, state: &mut AppState, area: Rect) {
     // Store content areas for mouse handling
     state.content_areas = pane_areas.clone();
 
-    // Render each pane
+    // Render each pane, storing text areas for mouse handling
+    let mut text_areas = Vec::with_capacity(pane_areas.len());
     for (i, pane_area) in pane_areas.iter().enumerate() {
         if i < state.panes.len() {
-            draw_pane(frame, state, *pane_area, i);
+            text_areas.push(draw_pane(frame, state, *pane_area, i));
         }
     }
+    state.pane_text_areas = text_areas;
 }
 
-/// Draw a single content pane
-fn draw_pane(frame: &mut Frame, state: &AppState, area: Rect, pane_idx: usize) {
+/// Draw a single content pane, returning the area used for text
+fn draw_pane(frame: &mut Frame, state: &mut AppState, area: Rect, pane_idx: usize) -> Rect {
     let theme = &state.theme;
-    let pane = &state.panes[pane_idx];
     let is_active = pane_idx == state.active_pane;
     let is_split = state.split_direction != SplitDirection::None;
 
-    // Determine if we need a border
-    let inner_area = if is_split {
+    let mut view = MarkdownView::new(&state.rendered_lines, theme)
+        .wrap(state.line_wrap)
+        .line_numbers(state.show_line_numbers)
+        .scroll_past_end(true);
+
+    // Border around each pane when split
+    if is_split {
         let border_color = if is_active {
             theme.border_focused
         } else {
             theme.border_unfocused
         };
-
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(border_color));
-
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
-        inner
-    } else {
-        area
-    };
-
-    // Calculate visible lines
-    let height = inner_area.height as usize;
-    let total_lines = state.rendered_lines.len();
-    let scroll = pane.scroll.min(total_lines.saturating_sub(1));
-
-    // Calculate line number gutter width if enabled
-    let line_num_width = if state.show_line_numbers {
-        // Width needed for largest line number + 1 space
-        let max_line = scroll + height;
-        let digits = if max_line == 0 {
-            1
-        } else {
-            (max_line as f64).log10().floor() as u16 + 1
-        };
-        digits + 2 // digits + space + separator
-    } else {
-        0
-    };
-
-    // Split area for line numbers and content
-    let (line_num_area, content_area) =
-        if state.show_line_numbers && line_num_width < inner_area.width {
-            let chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Length(line_num_width), Constraint::Min(1)])
-                .split(inner_area);
-            (Some(chunks[0]), chunks[1])
-        } else {
-            (None, inner_area)
-        };
-
-    // Get lines to display
-    let visible_lines: Vec<Line> = state
-        .rendered_lines
-        .iter()
-        .skip(scroll)
-        .take(height)
-        .cloned()
-        .collect();
-
-    // Apply search highlighting if active
-    let display_lines = if !pane.search_matches.is_empty() {
-        apply_search_highlighting(&visible_lines, pane, scroll, theme)
-    } else {
-        visible_lines
-    };
-
-    // Render line numbers if enabled
-    if let Some(ln_area) = line_num_area {
-        let line_numbers: Vec<Line> = (0..height)
-            .map(|i| {
-                let line_num = scroll + i + 1;
-                if line_num <= total_lines {
-                    Line::from(Span::styled(
-                        format!(
-                            "{:>width$} ",
-                            line_num,
-                            width = (line_num_width - 2) as usize
-                        ),
-                        Style::default().fg(theme.text_muted),
-                    ))
-                } else {
-                    Line::from(Span::styled(
-                        format!("{:>width$} ", "~", width = (line_num_width - 2) as usize),
-                        Style::default().fg(theme.text_muted),
-                    ))
-                }
-            })
-            .collect();
-
-        let ln_paragraph = Paragraph::new(line_numbers);
-        frame.render_widget(ln_paragraph, ln_area);
+        view = view.block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border_color)),
+        );
     }
 
-    // Render content
-    let content = if state.line_wrap {
-        Paragraph::new(display_lines).wrap(Wrap { trim: false })
-    } else {
-        // Apply horizontal scroll
-        let scrolled_lines: Vec<Line> = display_lines
-            .into_iter()
-            .map(|line| apply_horizontal_scroll(line, pane.horizontal_scroll))
-            .collect();
-        Paragraph::new(scrolled_lines)
-    };
-
-    frame.render_widget(content, content_area);
-
-    // Render scrollbar if needed
-    if total_lines > height {
-        let scrollbar_area = Rect {
-            x: content_area.x + content_area.width.saturating_sub(1),
-            y: content_area.y,
-            width: 1,
-            height: content_area.height,
-        };
-
-        let mut scrollbar_state =
-            ScrollbarState::new(total_lines.saturating_sub(height)).position(scroll);
-
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("▲"))
-            .end_symbol(Some("▼"));
-
-        frame.render_stateful_widget(scrollbar, scrollbar_area, &mut scrollbar_state);
-    }
-}
-
-/// Apply search highlighting to lines
-fn apply_search_highlighting(
-    lines: &[Line<'static>],
-    pane: &crate::app::PaneState,
-    scroll_offset: usize,
-    theme: &Theme,
-) -> Vec<Line<'static>> {
-    lines
-        .iter()
-        .enumerate()
-        .map(|(i, line)| {
-            let line_idx = scroll_offset + i;
-
-            // Check if any matches are on this line
-            let matches_on_line: Vec<_> = pane
-                .search_matches
-                .iter()
-                .filter(|m| m.line == line_idx)
-                .collect();
-
-            if matches_on_line.is_empty() {
-                line.clone()
-            } else {
-                // Rebuild line with highlighting
-                let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
-                let mut result_spans = Vec::new();
-                let mut last_end = 0;
-
-                for m in matches_on_line {
-                    // Text before match
-                    if m.start > last_end {
-                        result_spans.push(Span::styled(
-                            text[last_end..m.start].to_string(),
-                            Style::default().fg(theme.text),
-                        ));
-                    }
-
-                    // Highlighted match
-                    result_spans.push(Span::styled(
-                        text[m.start..m.end].to_string(),
-                        Style::default()
-                            .fg(theme.highlight_match_fg)
-                            .bg(theme.highlight_match_bg)
-                            .add_modifier(Modifier::BOLD),
-                    ));
-
-                    last_end = m.end;
-                }
-
-                // Text after last match
-                if last_end < text.len() {
-                    result_spans.push(Span::styled(
-                        text[last_end..].to_string(),
-                        Style::default().fg(theme.text),
-                    ));
-                }
-
-                Line::from(result_spans)
-            }
-        })
-        .collect()
-}
-
-/// Apply horizontal scroll to a line
-fn apply_horizontal_scroll(line: Line<'static>, offset: usize) -> Line<'static> {
-    if offset == 0 {
-        return line;
-    }
-
-    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
-    if offset >= text.len() {
-        return Line::from("");
+    // Tint lines changed by the last reload
+    if let Some(changed) = state
+        .file_path
+        .as_ref()
+        .and_then(|path| state.change_highlight.as_ref()?.lines_for(path))
+    {
+        view = view.changed_lines(changed);
     }
 
-    // Simple approach: just slice the text
-    Line::from(text[offset..].to_string())
+    let pane_view = &mut state.panes[pane_idx].view;
+    frame.render_stateful_widget(view, area, pane_view);
+    pane_view.text_area()
 }
 
 /// Draw the status bar
@@ -436,6 +373,8 @@ fn draw_status_bar(frame: &mut Frame, state: &AppState, area: Rect) {
         InputMode::SplitCommand => " SPLIT ",
         InputMode::UrlInput => " URL ",
         InputMode::BookmarkName => " BOOKMARK ",
+        InputMode::GlobalSearch => " FIND ",
+        InputMode::Command => " COMMAND ",
     };
 
     let mode_span = Span::styled(
@@ -448,7 +387,7 @@ fn draw_status_bar(frame: &mut Frame, state: &AppState, area: Rect) {
 
     // Line count and position
     let total = state.line_count();
-    let current = pane.scroll + 1;
+    let current = pane.view.scroll + 1;
     let position = format!(" {}/{} ", current, total);
 
     // Flags
@@ -456,6 +395,9 @@ fn draw_status_bar(frame: &mut Frame, state: &AppState, area: Rect) {
     if state.is_loading {
         flags.push_str("[LOADING]");
     }
+    if state.is_rendering() {
+        flags.push_str("[RENDERING]");
+    }
     if state.is_viewing_url() {
         flags.push_str("[URL]");
     }
@@ -465,6 +407,9 @@ fn draw_status_bar(frame: &mut Frame, state: &AppState, area: Rect) {
     if pane.search_is_regex {
         flags.push_str("[.*]");
     }
+    if pane.search_whole_word {
+        flags.push_str("[\\b]");
+    }
     if state.show_line_numbers {
         flags.push_str("[#]");
     }
@@ -485,14 +430,19 @@ fn draw_status_bar(frame: &mut Frame, state: &AppState, area: Rect) {
     if state.auto_reload {
         flags.push_str("[R]");
     }
+    if state.file_deleted {
+        flags.push_str("[deleted]");
+    }
 
     // Help hint
     let hint = match state.mode {
         InputMode::Normal => " ?:help O:url H:history m:bookmarks ",
-        InputMode::Search => " Enter:search Esc:cancel Ctrl+r:regex ",
+        InputMode::Search => " Enter:done Esc:cancel Ctrl+r:regex Ctrl+t:word ",
         InputMode::SplitCommand => " v:vsplit s:hsplit q:close w:cycle ",
         InputMode::UrlInput => " Enter:load Esc:cancel ",
         InputMode::BookmarkName => " Enter:save Esc:cancel ",
+        InputMode::GlobalSearch => " Enter:open Esc:close ↑/↓:select ",
+        InputMode::Command => " w [file]:export w!:overwrite q:quit Enter:run Esc:cancel ",
     };
 
     // Calculate padding
@@ -520,35 +470,97 @@ fn draw_search_bar(frame: &mut Frame, state: &mut AppState, area: Rect) {
     if state.mode == InputMode::Search {
         // Search input mode
         let prefix = Span::styled("/", Style::default().fg(theme.header_title));
+        let info = search_info(state.current_pane(), theme);
+        let info_width = info.width() as u16;
 
         // Render textarea
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(info_width),
+            ])
             .split(area);
 
         frame.render_widget(Paragraph::new(Line::from(prefix)), chunks[0]);
+        frame.render_widget(Paragraph::new(info), chunks[2]);
 
         let pane = state.current_pane_mut();
         frame.render_widget(&pane.search_textarea, chunks[1]);
+    } else if state.mode == InputMode::Command {
+        // Command line input
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(area);
+
+        let prefix = Span::styled(":", Style::default().fg(theme.header_title));
+        frame.render_widget(Paragraph::new(Line::from(prefix)), chunks[0]);
+        frame.render_widget(&state.command_textarea, chunks[1]);
     } else if let Some(msg) = &state.status_message {
         // Status message
         let message =
             Paragraph::new(msg.as_str()).style(Style::default().fg(theme.warning_message));
         frame.render_widget(message, area);
+    } else if !state.current_pane().view.search_matches.is_empty() {
+        // Active search: show query and match position
+        let pane = state.current_pane();
+        let mut spans = vec![Span::styled(
+            format!("/{} ", pane.search_query),
+            Style::default().fg(theme.text_muted),
+        )];
+        spans.extend(search_info(pane, theme).spans);
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
     } else {
         // Empty
         frame.render_widget(Paragraph::new(""), area);
     }
 }
 
+/// Build the search info line: option flags plus match counter or error
+fn search_info(pane: &crate::app::PaneState, theme: &Theme) -> Line<'static> {
+    let mut flags = String::new();
+    if pane.search_is_regex {
+        flags.push_str("[.*]");
+    }
+    if pane.search_whole_word {
+        flags.push_str("[\\b]");
+    }
+
+    let result = if let Some(error) = &pane.search_error {
+        Span::styled(
+            format!(" {} ", error),
+            Style::default().fg(theme.warning_message),
+        )
+    } else if pane.search_query.is_empty() {
+        Span::raw(" ")
+    } else if pane.view.search_matches.is_empty() {
+        Span::styled(" [0/0] ", Style::default().fg(theme.warning_message))
+    } else {
+        Span::styled(
+            format!(
+                " [{}/{}] ",
+                pane.view.current_match + 1,
+                pane.view.search_matches.len()
+            ),
+            Style::default().fg(theme.text),
+        )
+    };
+
+    Line::from(vec![
+        Span::styled(flags, Style::default().fg(theme.text_muted)),
+        result,
+    ])
+}
+
 /// Draw the help overlay
 fn draw_help_overlay(frame: &mut Frame, theme: &Theme) {
     let area = frame.area();
 
     // Calculate centered area
     let width = 60.min(area.width.saturating_sub(4));
-    let height = 30.min(area.height.saturating_sub(4));
+    let height = 33.min(area.height.saturating_sub(4));
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
 
@@ -586,13 +598,14 @@ fn draw_help_overlay(frame: &mut Frame, theme: &Theme) {
         )]),
         Line::from("  /              Start search"),
         Line::from("  n/N            Next/prev match"),
-        Line::from("  Ctrl+r         Toggle regex"),
+        Line::from("  Ctrl+r/Ctrl+t  Toggle regex / whole word"),
+        Line::from("  F / Ctrl+f     Search open buffers / project"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "View",
             Style::default().add_modifier(Modifier::BOLD),
         )]),
-        Line::from("  b              Toggle outline"),
+        Line::from("  b / t          Toggle outline / file tree"),
         Line::from("  < >  [ ]       Resize outline panel"),
         Line::from("  w/#            Line wrap / numbers"),
         Line::from("  Ctrl+s/R       Syntax hl / auto-reload"),
@@ -608,6 +621,7 @@ fn draw_help_overlay(frame: &mut Frame, theme: &Theme) {
         Line::from("  m/M            Bookmarks / add bookmark"),
         Line::from("  B              Buffer list"),
         Line::from("  Ctrl+n/p/x     Next/prev/close buffer"),
+        Line::from("  :w [FILE]      Export (.html/.txt/.ansi)"),
         Line::from(""),
         Line::from("  S  Settings    q  Quit    ?  Close"),
     ];
@@ -623,7 +637,7 @@ fn draw_settings_overlay(frame: &mut Frame, state: &AppState) {
     let area = frame.area();
 
     // Calculate centered area
-    let width = 40.min(area.width.saturating_sub(4));
+    let width = 48.min(area.width.saturating_sub(4));
     let height = 14.min(area.height.saturating_sub(4));
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
@@ -667,10 +681,21 @@ fn draw_settings_overlay(frame: &mut Frame, state: &AppState) {
             }
             .to_string(),
         ),
+        (
+            "Code Theme",
+            match &state.syntax_theme {
+                Some(name) => name.clone(),
+                None => format!("{} (auto)", state.theme.syntax_theme),
+            },
+        ),
         (
             "Auto Reload",
             if state.auto_reload { "ON" } else { "OFF" }.to_string(),
         ),
+        (
+            "Jump to Changes",
+            if state.jump_to_change { "ON" } else { "OFF" }.to_string(),
+        ),
         ("Save Settings", "[Enter]".to_string()),
     ];
 
@@ -706,8 +731,8 @@ fn draw_file_picker(frame: &mut Frame, state: &AppState) {
     let area = frame.area();
 
     // Calculate centered area
-    let width = 50.min(area.width.saturating_sub(4));
-    let height = 15.min(area.height.saturating_sub(4));
+    let width = PREVIEW_OVERLAY_WIDTH.min(area.width.saturating_sub(4));
+    let height = 20.min(area.height.saturating_sub(4));
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
 
@@ -717,8 +742,10 @@ fn draw_file_picker(frame: &mut Frame, state: &AppState) {
     frame.render_widget(Clear, popup_area);
 
     // Draw block
+    let title = format!(" Open File: {} ", state.file_picker_dir.display());
     let block = Block::default()
-        .title(" Open File ")
+        .title(title)
+        .title_bottom(Line::from(" Enter:open  Bksp:parent  Esc:close ").right_aligned())
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.help_border))
         .style(Style::default().bg(theme.help_bg));
@@ -726,17 +753,38 @@ fn draw_file_picker(frame: &mut Frame, state: &AppState) {
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
-    if state.file_picker_files.is_empty() {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    // Filter line
+    let input_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(chunks[0]);
+    frame.render_widget(
+        Paragraph::new(Span::styled("> ", Style::default().fg(theme.header_title))),
+        input_chunks[0],
+    );
+    frame.render_widget(&state.file_picker_textarea, input_chunks[1]);
+
+    let (list_area, preview_area) = split_preview(chunks[1]);
+    if let Some(preview_area) = preview_area {
+        draw_preview(frame, state, preview_area);
+    }
+
+    if state.file_picker_entries.is_empty() {
         let empty =
-            Paragraph::new("No markdown files found").style(Style::default().fg(theme.empty_state));
-        frame.render_widget(empty, inner);
+            Paragraph::new("No matching files").style(Style::default().fg(theme.empty_state));
+        frame.render_widget(empty, list_area);
         return;
     }
 
     // Calculate visible range (scrolling if needed)
-    let visible_height = inner.height as usize;
+    let visible_height = list_area.height as usize;
     let selected = state.file_picker_selected;
-    let total = state.file_picker_files.len();
+    let total = state.file_picker_entries.len();
 
     let start = if selected >= visible_height {
         selected - visible_height + 1
@@ -745,35 +793,91 @@ fn draw_file_picker(frame: &mut Frame, state: &AppState) {
     };
     let end = (start + visible_height).min(total);
 
-    // File list
+    // Entry list, with matched characters highlighted
+    let match_style = Style::default()
+        .fg(theme.highlight_match_fg)
+        .bg(theme.highlight_match_bg);
+
     let items: Vec<ListItem> = state
-        .file_picker_files
+        .file_picker_entries
         .iter()
         .enumerate()
         .skip(start)
         .take(end - start)
-        .map(|(i, path)| {
-            let filename = path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| path.to_string_lossy().to_string());
-
+        .map(|(i, entry)| {
             let marker = if i == selected { "> " } else { "  " };
 
             let style = if i == selected {
                 Style::default()
                     .fg(theme.outline_selected)
                     .add_modifier(Modifier::BOLD)
+            } else if entry.is_dir {
+                Style::default().fg(theme.link)
             } else {
                 Style::default().fg(theme.text)
             };
 
-            ListItem::new(format!("{}{}", marker, filename)).style(style)
+            let mut spans = vec![Span::styled(marker, style)];
+            let mut matched = entry.matched.iter().peekable();
+            for (ci, c) in entry.display.chars().enumerate() {
+                if matched.next_if_eq(&&ci).is_some() {
+                    spans.push(Span::styled(c.to_string(), match_style));
+                } else {
+                    spans.push(Span::styled(c.to_string(), style));
+                }
+            }
+
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
     let list = List::new(items);
-    frame.render_widget(list, inner);
+    frame.render_widget(list, list_area);
+}
+
+/// Split an overlay's list area to make room for a preview pane
+/// (no preview when the overlay is too narrow)
+fn split_preview(area: Rect) -> (Rect, Option<Rect>) {
+    if area.width < 60 {
+        return (area, None);
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+    (chunks[0], Some(chunks[1]))
+}
+
+/// Draw the preview of the document highlighted in a list overlay
+fn draw_preview(frame: &mut Frame, state: &AppState, area: Rect) {
+    let theme = &state.theme;
+
+    let block = Block::default()
+        .title(" Preview ")
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(theme.help_border));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let paragraph = match state.current_preview() {
+        Some(preview) => {
+            let lines: Vec<Line> = preview
+                .lines
+                .iter()
+                .take(inner.height as usize)
+                .cloned()
+                .collect();
+            Paragraph::new(lines)
+        }
+        None if state.preview_location.is_some() => {
+            Paragraph::new("(no preview available)").style(Style::default().fg(theme.empty_state))
+        }
+        None => Paragraph::new(""),
+    };
+
+    frame.render_widget(paragraph, inner);
 }
 
 /// Draw the buffer list overlay
@@ -828,14 +932,17 @@ fn draw_buffer_list(frame: &mut Frame, state: &AppState) {
         .skip(start)
         .take(end - start)
         .map(|(i, buffer)| {
-            let filename = buffer
-                .file_path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| buffer.file_path.to_string_lossy().to_string());
+            let filename = buffer.display_name();
 
             let marker = if i == selected { "> " } else { "  " };
             let active_marker = if i == state.active_buffer { " *" } else { "" };
+            let disk_marker = if buffer.deleted {
+                " [deleted]"
+            } else if buffer.changed_on_disk {
+                " [changed]"
+            } else {
+                ""
+            };
 
             let style = if i == selected {
                 Style::default()
@@ -848,10 +955,11 @@ fn draw_buffer_list(frame: &mut Frame, state: &AppState) {
             };
 
             ListItem::new(format!(
-                "{}[{}] {}{}",
+                "{}[{}] {}{}{}",
                 marker,
                 i + 1,
                 filename,
+                disk_marker,
                 active_marker
             ))
             .style(style)
@@ -862,14 +970,89 @@ fn draw_buffer_list(frame: &mut Frame, state: &AppState) {
     frame.render_widget(list, inner);
 }
 
+/// Draw the code theme picker
+fn draw_syntax_theme_picker(frame: &mut Frame, state: &AppState) {
+    let theme = &state.theme;
+    let area = frame.area();
+
+    // Calculate centered area
+    let width = 50.min(area.width.saturating_sub(4));
+    let height = 15.min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+
+    let popup_area = Rect::new(x, y, width, height);
+
+    // Clear background
+    frame.render_widget(Clear, popup_area);
+
+    // Draw block
+    let block = Block::default()
+        .title(" Code Theme ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.help_border))
+        .style(Style::default().bg(theme.help_bg));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    // The theme's own syntax theme, then every available one
+    let choices: Vec<(String, bool)> = std::iter::once((
+        format!("{} (auto)", theme.syntax_theme),
+        state.syntax_theme.is_none(),
+    ))
+    .chain(
+        state
+            .syntax_theme_choices
+            .iter()
+            .map(|name| (name.clone(), state.syntax_theme.as_ref() == Some(name))),
+    )
+    .collect();
+
+    // Calculate visible range (scrolling if needed)
+    let visible_height = inner.height as usize;
+    let selected = state.syntax_theme_selected;
+    let start = if selected >= visible_height {
+        selected - visible_height + 1
+    } else {
+        0
+    };
+
+    let items: Vec<ListItem> = choices
+        .into_iter()
+        .enumerate()
+        .skip(start)
+        .take(visible_height)
+        .map(|(i, (name, in_use))| {
+            let marker = if i == selected { "> " } else { "  " };
+            let in_use_marker = if in_use { " *" } else { "" };
+
+            let style = if i == selected {
+                Style::default()
+                    .fg(theme.outline_selected)
+                    .add_modifier(Modifier::BOLD)
+            } else if in_use {
+                Style::default().fg(theme.heading_1)
+            } else {
+                Style::default().fg(theme.text)
+            };
+
+            ListItem::new(format!("{}{}{}", marker, name, in_use_marker)).style(style)
+        })
+        .collect();
+
+    let list = List::new(items);
+    frame.render_widget(list, inner);
+}
+
 /// Draw the history overlay
 fn draw_history_overlay(frame: &mut Frame, state: &AppState) {
     let theme = &state.theme;
     let area = frame.area();
 
     // Calculate centered area
-    let width = 60.min(area.width.saturating_sub(4));
-    let height = 15.min(area.height.saturating_sub(4));
+    let width = PREVIEW_OVERLAY_WIDTH.min(area.width.saturating_sub(4));
+    let height = 20.min(area.height.saturating_sub(4));
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
 
@@ -889,6 +1072,11 @@ fn draw_history_overlay(frame: &mut Frame, state: &AppState) {
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
+    let (inner, preview_area) = split_preview(inner);
+    if let Some(preview_area) = preview_area {
+        draw_preview(frame, state, preview_area);
+    }
+
     if state.history.entries().is_empty() {
         let empty = Paragraph::new("No history yet").style(Style::default().fg(theme.empty_state));
         frame.render_widget(empty, inner);
@@ -941,8 +1129,8 @@ fn draw_bookmarks_overlay(frame: &mut Frame, state: &AppState) {
     let area = frame.area();
 
     // Calculate centered area
-    let width = 60.min(area.width.saturating_sub(4));
-    let height = 15.min(area.height.saturating_sub(4));
+    let width = PREVIEW_OVERLAY_WIDTH.min(area.width.saturating_sub(4));
+    let height = 20.min(area.height.saturating_sub(4));
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
 
@@ -962,6 +1150,11 @@ fn draw_bookmarks_overlay(frame: &mut Frame, state: &AppState) {
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
+    let (inner, preview_area) = split_preview(inner);
+    if let Some(preview_area) = preview_area {
+        draw_preview(frame, state, preview_area);
+    }
+
     if state.bookmarks.entries().is_empty() {
         let empty = Paragraph::new("No bookmarks yet (M to add)")
             .style(Style::default().fg(theme.empty_state));
@@ -1058,3 +1251,220 @@ fn draw_bookmark_name_input(frame: &mut Frame, state: &mut AppState) {
     // Render textarea
     frame.render_widget(&state.bookmark_name_textarea, inner);
 }
+
+/// Draw the search-all-buffers overlay
+fn draw_global_search(frame: &mut Frame, state: &mut AppState) {
+    let theme = &state.theme;
+    let area = frame.area();
+
+    // Calculate centered area
+    let width = (area.width * 4 / 5).min(area.width.saturating_sub(4));
+    let height = (area.height * 3 / 4).min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+
+    let popup_area = Rect::new(x, y, width, height);
+
+    // Clear background
+    frame.render_widget(Clear, popup_area);
+
+    // Draw block
+    let block = Block::default()
+        .title(format!(
+            " {} ({}){} - Tab:switch ",
+            match state.global_search_scope {
+                SearchScope::Buffers => "Search Buffers",
+                SearchScope::Project => "Search Project",
+            },
+            state.global_search_results.len(),
+            if state.project_search_running {
+                " searching..."
+            } else {
+                ""
+            }
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.help_border))
+        .style(Style::default().bg(theme.help_bg));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    draw_results_input(
+        frame,
+        &state.global_search_textarea,
+        state.global_search_options,
+        state.global_search_error.as_deref(),
+        theme,
+        chunks[0],
+    );
+
+    let group_name = |hit: &barkdocs::search::SearchHit| match &hit.source {
+        barkdocs::search::HitSource::Buffer(idx) => state
+            .buffers
+            .get(*idx)
+            .map(|b| b.display_name())
+            .unwrap_or_default(),
+        barkdocs::search::HitSource::File(path) => path
+            .strip_prefix(".")
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string(),
+    };
+    let empty_message = if state.global_search_scope == SearchScope::Project
+        && state.project_search_last.is_none()
+    {
+        "Press Enter to search markdown files under the current directory"
+    } else if state.project_search_running {
+        "Searching..."
+    } else {
+        "No results"
+    };
+    draw_search_results(
+        frame,
+        &state.global_search_results,
+        state.global_search_selected,
+        group_name,
+        empty_message,
+        theme,
+        chunks[1],
+    );
+}
+
+/// Draw the query line of a search results overlay
+fn draw_results_input(
+    frame: &mut Frame,
+    textarea: &tui_textarea::TextArea<'static>,
+    options: barkdocs::search::SearchOptions,
+    error: Option<&str>,
+    theme: &Theme,
+    area: Rect,
+) {
+    let mut flags = String::new();
+    if options.regex {
+        flags.push_str("[.*]");
+    }
+    if options.whole_word {
+        flags.push_str("[\\b]");
+    }
+    let info = match error {
+        Some(e) => Span::styled(
+            format!("{} {} ", flags, e),
+            Style::default().fg(theme.warning_message),
+        ),
+        None => Span::styled(format!("{} ", flags), Style::default().fg(theme.text_muted)),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(info.width() as u16),
+        ])
+        .split(area);
+
+    frame.render_widget(
+        Paragraph::new(Span::styled("/ ", Style::default().fg(theme.header_title))),
+        chunks[0],
+    );
+    frame.render_widget(textarea, chunks[1]);
+    frame.render_widget(Paragraph::new(info), chunks[2]);
+}
+
+/// Draw search hits grouped by document, with heading and snippet
+fn draw_search_results(
+    frame: &mut Frame,
+    hits: &[barkdocs::search::SearchHit],
+    selected: usize,
+    group_name: impl Fn(&barkdocs::search::SearchHit) -> String,
+    empty_message: &str,
+    theme: &Theme,
+    area: Rect,
+) {
+    if hits.is_empty() {
+        let empty = Paragraph::new(empty_message).style(Style::default().fg(theme.empty_state));
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    // Build rows, inserting a header whenever the document changes
+    let mut rows: Vec<ListItem> = Vec::new();
+    let mut selected_row = 0;
+    let mut last_group: Option<String> = None;
+
+    for (i, hit) in hits.iter().enumerate() {
+        let group = group_name(hit);
+        if last_group.as_ref() != Some(&group) {
+            rows.push(ListItem::new(Line::from(Span::styled(
+                group.clone(),
+                Style::default()
+                    .fg(theme.heading_2)
+                    .add_modifier(Modifier::BOLD),
+            ))));
+            last_group = Some(group);
+        }
+
+        if i == selected {
+            selected_row = rows.len();
+        }
+
+        let is_selected = i == selected;
+        let base = if is_selected {
+            Style::default()
+                .fg(theme.outline_selected)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+
+        let mut spans = vec![Span::styled(if is_selected { "> " } else { "  " }, base)];
+        spans.push(Span::styled(
+            format!("{:>5}: ", hit.line + 1),
+            Style::default().fg(theme.text_muted),
+        ));
+        if let Some(heading) = &hit.heading {
+            spans.push(Span::styled(
+                format!("{} › ", heading),
+                Style::default().fg(theme.outline_heading),
+            ));
+        }
+        spans.push(Span::styled(
+            hit.snippet[..hit.snippet_start].to_string(),
+            base,
+        ));
+        spans.push(Span::styled(
+            hit.snippet[hit.snippet_start..hit.snippet_end].to_string(),
+            Style::default()
+                .fg(theme.highlight_match_fg)
+                .bg(theme.highlight_match_bg),
+        ));
+        spans.push(Span::styled(
+            hit.snippet[hit.snippet_end..].to_string(),
+            base,
+        ));
+
+        rows.push(ListItem::new(Line::from(spans)));
+    }
+
+    // Scroll so the selected row stays visible
+    let visible_height = area.height as usize;
+    let start = if selected_row >= visible_height {
+        selected_row - visible_height + 1
+    } else {
+        0
+    };
+
+    let list = List::new(
+        rows.into_iter()
+            .skip(start)
+            .take(visible_height)
+            .collect::<Vec<_>>(),
+    );
+    frame.render_widget(list, area);
+}
@@ -0,0 +1,659 @@
+use crate::search::{self, SearchMatch, SearchOptions};
+use crate::theme::Theme;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget, Wrap,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Scroll and search state for a [`MarkdownView`]
+#[derive(Clone, Debug, Default)]
+pub struct MarkdownViewState {
+    /// First visible line
+    pub scroll: usize,
+    /// Display columns scrolled to the right (when wrapping is off)
+    pub horizontal_scroll: usize,
+    /// Matches of the current search
+    pub search_matches: Vec<SearchMatch>,
+    /// Index into `search_matches` of the selected match
+    pub current_match: usize,
+    /// Line count, viewport height and text area seen by the last render
+    line_count: usize,
+    viewport_height: usize,
+    text_area: Rect,
+}
+
+impl MarkdownViewState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Area the lines were drawn in by the last render (inside the block and
+    /// line numbers)
+    pub fn text_area(&self) -> Rect {
+        self.text_area
+    }
+
+    /// Highest useful scroll position
+    fn max_scroll(&self) -> usize {
+        self.line_count.saturating_sub(self.viewport_height.max(1))
+    }
+
+    /// Scroll down by `lines`
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(self.max_scroll());
+    }
+
+    /// Scroll up by `lines`
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Scroll down by one viewport
+    pub fn page_down(&mut self) {
+        self.scroll_down(self.viewport_height.max(1));
+    }
+
+    /// Scroll up by one viewport
+    pub fn page_up(&mut self) {
+        self.scroll_up(self.viewport_height.max(1));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    /// Search `lines`, selecting the first match at or below the current
+    /// scroll position. Returns the number of matches, or the regex error.
+    pub fn search(
+        &mut self,
+        lines: &[Line],
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<usize, String> {
+        self.line_count = lines.len();
+        if query.is_empty() {
+            self.clear_search();
+            return Ok(0);
+        }
+
+        let pattern = search::build_pattern(query, options)?;
+        self.search_matches = search::find_matches(lines, &pattern);
+        self.current_match = self
+            .search_matches
+            .iter()
+            .position(|m| m.line >= self.scroll)
+            .unwrap_or(0);
+        self.scroll_to_current_match();
+        Ok(self.search_matches.len())
+    }
+
+    /// Remove search highlighting
+    pub fn clear_search(&mut self) {
+        self.search_matches.clear();
+        self.current_match = 0;
+    }
+
+    /// The selected match, if any
+    pub fn current_match(&self) -> Option<&SearchMatch> {
+        self.search_matches.get(self.current_match)
+    }
+
+    /// Select the next match (wrapping around) and scroll to it
+    pub fn next_match(&mut self) {
+        if !self.search_matches.is_empty() {
+            self.current_match = (self.current_match + 1) % self.search_matches.len();
+            self.scroll_to_current_match();
+        }
+    }
+
+    /// Select the previous match (wrapping around) and scroll to it
+    pub fn prev_match(&mut self) {
+        if !self.search_matches.is_empty() {
+            self.current_match = self
+                .current_match
+                .checked_sub(1)
+                .unwrap_or(self.search_matches.len() - 1);
+            self.scroll_to_current_match();
+        }
+    }
+
+    /// Scroll so the selected match is visible
+    fn scroll_to_current_match(&mut self) {
+        let Some(line) = self.current_match().map(|m| m.line) else {
+            return;
+        };
+        let height = self.viewport_height.max(1);
+        if line < self.scroll || line >= self.scroll + height {
+            self.scroll = line.saturating_sub(height / 3);
+        }
+    }
+}
+
+/// A scrollable, searchable view of rendered markdown lines
+/// (from [`Document::render_with_highlighting`](crate::markdown::Document::render_with_highlighting))
+pub struct MarkdownView<'a> {
+    lines: &'a [Line<'static>],
+    theme: &'a Theme,
+    wrap: bool,
+    scrollbar: bool,
+    line_numbers: bool,
+    changed_lines: Option<&'a [usize]>,
+    scroll_past_end: bool,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> MarkdownView<'a> {
+    pub fn new(lines: &'a [Line<'static>], theme: &'a Theme) -> Self {
+        Self {
+            lines,
+            theme,
+            wrap: true,
+            scrollbar: true,
+            line_numbers: false,
+            changed_lines: None,
+            scroll_past_end: false,
+            block: None,
+        }
+    }
+
+    /// Wrap long lines (on by default); otherwise they scroll horizontally
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Show a scrollbar when the content is taller than the view (on by default)
+    pub fn scrollbar(mut self, scrollbar: bool) -> Self {
+        self.scrollbar = scrollbar;
+        self
+    }
+
+    /// Show line numbers in a gutter on the left
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Tint these lines (sorted indices into `lines`) with the theme's
+    /// changed-line background
+    pub fn changed_lines(mut self, changed_lines: &'a [usize]) -> Self {
+        self.changed_lines = Some(changed_lines);
+        self
+    }
+
+    /// Let the view scroll until only the last line is left at the top,
+    /// instead of stopping when it reaches the bottom
+    pub fn scroll_past_end(mut self, scroll_past_end: bool) -> Self {
+        self.scroll_past_end = scroll_past_end;
+        self
+    }
+
+    /// Surround the view with a block
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl StatefulWidget for MarkdownView<'_> {
+    type State = MarkdownViewState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = match self.block {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+
+        let height = area.height as usize;
+        state.line_count = self.lines.len();
+        state.viewport_height = height;
+        state.scroll = if self.scroll_past_end {
+            state.scroll.min(self.lines.len().saturating_sub(1))
+        } else {
+            state.scroll.min(state.max_scroll())
+        };
+
+        // Gutter wide enough for the last line number in view, plus a space
+        // and a separator
+        let gutter_width = (state.scroll + height).max(1).ilog10() as u16 + 3;
+        let (gutter, area) = if self.line_numbers && gutter_width < area.width {
+            let [gutter, text] =
+                Layout::horizontal([Constraint::Length(gutter_width), Constraint::Min(1)])
+                    .areas(area);
+            (Some(gutter), text)
+        } else {
+            (None, area)
+        };
+        state.text_area = area;
+
+        let visible: Vec<Line<'static>> = self
+            .lines
+            .iter()
+            .skip(state.scroll)
+            .take(height)
+            .cloned()
+            .collect();
+        let visible = match self.changed_lines {
+            Some(changed) => {
+                tint_changed_lines(visible, changed, state.scroll, self.theme.changed_line_bg)
+            }
+            None => visible,
+        };
+        let visible = highlight_matches(&visible, &state.search_matches, state.scroll, self.theme);
+
+        if let Some(gutter) = gutter {
+            let number_style = Style::default().fg(self.theme.text_muted);
+            let digits = (gutter_width - 2) as usize;
+            let numbers: Vec<Line> = (state.scroll + 1..=state.scroll + height)
+                .map(|number| {
+                    let label = if number <= self.lines.len() {
+                        format!("{:>digits$} ", number)
+                    } else {
+                        format!("{:>digits$} ", "~")
+                    };
+                    Line::from(Span::styled(label, number_style))
+                })
+                .collect();
+            Paragraph::new(numbers).render(gutter, buf);
+        }
+
+        let paragraph = if self.wrap {
+            Paragraph::new(visible).wrap(Wrap { trim: false })
+        } else {
+            let scrolled: Vec<Line<'static>> = visible
+                .into_iter()
+                .map(|line| scroll_horizontally(line, state.horizontal_scroll))
+                .collect();
+            Paragraph::new(scrolled)
+        };
+        paragraph.render(area, buf);
+
+        if self.scrollbar && self.lines.len() > height {
+            let mut scrollbar_state =
+                ScrollbarState::new(state.max_scroll()).position(state.scroll);
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some("▲"))
+                .end_symbol(Some("▼"))
+                .render(area, buf, &mut scrollbar_state);
+        }
+    }
+}
+
+/// Highlight search matches in lines starting at rendered line `first_line`,
+/// keeping the lines' own styling around the matches
+pub fn highlight_matches(
+    lines: &[Line<'static>],
+    matches: &[SearchMatch],
+    first_line: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let highlight = Style::default()
+        .fg(theme.highlight_match_fg)
+        .bg(theme.highlight_match_bg)
+        .add_modifier(Modifier::BOLD);
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line_matches: Vec<&SearchMatch> = matches
+                .iter()
+                .filter(|m| m.line == first_line + i)
+                .collect();
+            if line_matches.is_empty() {
+                return line.clone();
+            }
+
+            let mut spans = Vec::new();
+            let mut offset = 0;
+            for span in &line.spans {
+                let text = span.content.as_ref();
+                let span_end = offset + text.len();
+
+                // Cut the span at match boundaries inside it
+                let mut cuts = vec![offset, span_end];
+                for m in &line_matches {
+                    cuts.extend(
+                        [m.start, m.end]
+                            .into_iter()
+                            .filter(|&c| c > offset && c < span_end),
+                    );
+                }
+                cuts.sort_unstable();
+                cuts.dedup();
+
+                for pair in cuts.windows(2) {
+                    let (start, end) = (pair[0], pair[1]);
+                    let Some(piece) = text.get(start - offset..end - offset) else {
+                        continue;
+                    };
+                    let in_match = line_matches
+                        .iter()
+                        .any(|m| start >= m.start && end <= m.end);
+                    let style = if in_match {
+                        span.style.patch(highlight)
+                    } else {
+                        span.style
+                    };
+                    spans.push(Span::styled(piece.to_string(), style));
+                }
+                offset = span_end;
+            }
+
+            Line::from(spans).style(line.style)
+        })
+        .collect()
+}
+
+/// Drop the first `offset` display columns of a line, keeping span styles. A
+/// wide character cut in half leaves a space in its place.
+pub fn scroll_horizontally(line: Line<'static>, offset: usize) -> Line<'static> {
+    if offset == 0 {
+        return line;
+    }
+
+    let mut skipped = 0;
+    let mut spans = Vec::new();
+    for span in line.spans {
+        if skipped >= offset {
+            spans.push(span);
+            continue;
+        }
+
+        let mut kept = String::new();
+        for grapheme in span.styled_graphemes(Style::default()) {
+            if skipped >= offset {
+                kept.push_str(grapheme.symbol);
+                continue;
+            }
+            skipped += grapheme.symbol.width();
+            if skipped > offset {
+                kept.push_str(&" ".repeat(skipped - offset));
+            }
+        }
+        if !kept.is_empty() {
+            spans.push(Span::styled(kept, span.style));
+        }
+    }
+
+    Line { spans, ..line }
+}
+
+/// Give changed lines (indices into the whole document, `lines` starting at
+/// `first_line`) a background color
+pub fn tint_changed_lines(
+    lines: Vec<Line<'static>>,
+    changed: &[usize],
+    first_line: usize,
+    color: Color,
+) -> Vec<Line<'static>> {
+    let tint = Style::default().bg(color);
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut line)| {
+            if changed.binary_search(&(first_line + i)).is_ok() {
+                line.style = line.style.patch(tint);
+                for span in &mut line.spans {
+                    span.style = span.style.patch(tint);
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+/// Display column in `line` shown at `col` when it is scrolled `offset`
+/// display columns to the right without wrapping (see [`scroll_horizontally`]).
+/// Like [`wrapped_column`], a wide character maps to its first column, and
+/// `None` is past the end of the line.
+pub fn scrolled_column(line: &Line<'_>, offset: usize, col: usize) -> Option<usize> {
+    let target = offset + col;
+    let mut column = 0;
+    for grapheme in line.styled_graphemes(Style::default()) {
+        let end = column + grapheme.symbol.width();
+        if target < end {
+            return Some(column);
+        }
+        column = end;
+    }
+    None
+}
+
+/// Display column in `line` shown at `col` of wrapped row `row` when the line
+/// is wrapped to `width` like [`MarkdownView`] does. `None` past the end of
+/// the row.
+pub fn wrapped_column(line: &Line<'_>, width: u16, row: usize, col: usize) -> Option<usize> {
+    let width = width.max(1);
+    let paragraph = Paragraph::new(line.clone()).wrap(Wrap { trim: false });
+    let height = paragraph.line_count(width).min(u16::MAX as usize);
+    if row >= height || col >= width as usize {
+        return None;
+    }
+    let area = Rect::new(0, 0, width, row as u16 + 1);
+    let mut buffer = Buffer::empty(area);
+    paragraph.render(area, &mut buffer);
+
+    // Wrapping keeps the line's characters in order and only drops spaces at
+    // row breaks, so walking the cells alongside them finds each one's column
+    let mut graphemes = line
+        .styled_graphemes(Style::default())
+        .scan(0, |column, grapheme| {
+            let start = *column;
+            *column += grapheme.symbol.width();
+            Some((start, grapheme.symbol))
+        })
+        .peekable();
+
+    for y in 0..=row as u16 {
+        let mut x = 0;
+        while x < width {
+            let symbol = buffer[(x, y)].symbol();
+            let column = loop {
+                match graphemes.peek() {
+                    Some(&(start, grapheme)) if grapheme == symbol => {
+                        graphemes.next();
+                        break Some(start);
+                    }
+                    Some((_, grapheme)) if grapheme.trim().is_empty() => {
+                        graphemes.next();
+                    }
+                    // Padding after the end of the row
+                    _ => break None,
+                }
+            };
+
+            let cell_width = symbol.width().max(1) as u16;
+            if y as usize == row && (x..x + cell_width).contains(&(col as u16)) {
+                return column;
+            }
+            x += cell_width;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    fn lines(count: usize) -> Vec<Line<'static>> {
+        (0..count)
+            .map(|i| Line::from(format!("line {}", i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_highlight_keeps_span_styles() {
+        let theme = Theme::default();
+        let bold = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+        let line = Line::from(vec![Span::raw("foo "), Span::styled("barbaz", bold)]);
+        let matches = vec![SearchMatch {
+            line: 3,
+            start: 2,
+            end: 7,
+        }];
+
+        let result = highlight_matches(&[line], &matches, 3, &theme);
+        let spans: Vec<(&str, Style)> = result[0]
+            .spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style))
+            .collect();
+        assert_eq!(spans.len(), 4);
+        assert_eq!(spans[0], ("fo", Style::default()));
+        assert_eq!(spans[1].0, "o ");
+        assert_eq!(spans[1].1.bg, Some(theme.highlight_match_bg));
+        assert_eq!(spans[2].0, "bar");
+        assert_eq!(spans[2].1.bg, Some(theme.highlight_match_bg));
+        assert_eq!(spans[3], ("baz", bold));
+    }
+
+    #[test]
+    fn test_wrapped_column_follows_word_wrap() {
+        // Wraps as "hello " / "world " / "foo"
+        let line = Line::from(vec![Span::raw("hello "), Span::raw("world foo")]);
+        assert_eq!(wrapped_column(&line, 8, 0, 4), Some(4));
+        assert_eq!(wrapped_column(&line, 8, 1, 0), Some(6));
+        assert_eq!(wrapped_column(&line, 8, 2, 1), Some(13));
+        assert_eq!(wrapped_column(&line, 8, 2, 5), None);
+        assert_eq!(wrapped_column(&line, 8, 3, 0), None);
+
+        let wide = Line::from("日本 語");
+        assert_eq!(wrapped_column(&wide, 4, 0, 3), Some(2));
+        assert_eq!(wrapped_column(&wide, 4, 1, 0), Some(5));
+    }
+
+    #[test]
+    fn test_scrolled_column_counts_display_columns() {
+        let line = Line::from(vec![Span::raw("é日"), Span::raw("本x")]);
+        assert_eq!(scrolled_column(&line, 0, 0), Some(0));
+        assert_eq!(scrolled_column(&line, 0, 2), Some(1));
+        assert_eq!(scrolled_column(&line, 2, 1), Some(3));
+        assert_eq!(scrolled_column(&line, 2, 3), Some(5));
+        assert_eq!(scrolled_column(&line, 2, 4), None);
+    }
+
+    #[test]
+    fn test_tint_changed_lines() {
+        let lines = vec![Line::from("a"), Line::from("b"), Line::from("c")];
+        let tinted = tint_changed_lines(lines, &[11], 10, Color::Green);
+        assert_eq!(tinted[0].style.bg, None);
+        assert_eq!(tinted[1].style.bg, Some(Color::Green));
+        assert_eq!(tinted[1].spans[0].style.bg, Some(Color::Green));
+        assert_eq!(tinted[2].style.bg, None);
+    }
+
+    #[test]
+    fn test_scroll_horizontally_by_display_columns() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let line = Line::from(vec![Span::raw("é日"), Span::styled("本 ✨x", bold)]);
+
+        // Cuts "日" in half, leaving a space
+        let scrolled = scroll_horizontally(line.clone(), 2);
+        assert_eq!(scrolled.spans[0].content, " ");
+        assert_eq!(scrolled.spans[1].content, "本 ✨x");
+        assert_eq!(scrolled.spans[1].style, bold);
+
+        let scrolled = scroll_horizontally(line.clone(), 6);
+        assert_eq!(scrolled.spans.len(), 1);
+        assert_eq!(scrolled.spans[0].content, "✨x");
+        assert_eq!(scrolled.spans[0].style, bold);
+
+        assert!(scroll_horizontally(line, 20).spans.is_empty());
+    }
+
+    #[test]
+    fn test_render_scrolls_and_clamps() {
+        let theme = Theme::default();
+        let lines = lines(20);
+        let mut state = MarkdownViewState::new();
+        state.scroll = 100;
+
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+        MarkdownView::new(&lines, &theme)
+            .scrollbar(false)
+            .render(area, &mut buf, &mut state);
+        assert_eq!(state.scroll, 15);
+        assert_eq!(buf[(0, 0)].symbol(), "l");
+        assert_eq!(buf[(5, 4)].symbol(), "1");
+        assert_eq!(buf[(6, 4)].symbol(), "9");
+
+        state.page_up();
+        assert_eq!(state.scroll, 10);
+        state.scroll_to_bottom();
+        assert_eq!(state.scroll, 15);
+    }
+
+    #[test]
+    fn test_line_numbers_and_changed_lines() {
+        let theme = Theme::default();
+        let lines = lines(20);
+        let mut state = MarkdownViewState::new();
+        state.scroll = 100;
+
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+        MarkdownView::new(&lines, &theme)
+            .scrollbar(false)
+            .line_numbers(true)
+            .changed_lines(&[19])
+            .scroll_past_end(true)
+            .render(area, &mut buf, &mut state);
+        assert_eq!(state.scroll, 19);
+        // Two digits, a space and a separator column
+        assert_eq!(state.text_area(), Rect::new(4, 0, 16, 5));
+        assert_eq!(buf[(0, 0)].symbol(), "2");
+        assert_eq!(buf[(1, 1)].symbol(), "~");
+        assert_eq!(buf[(4, 0)].symbol(), "l");
+        assert_eq!(buf[(4, 0)].bg, theme.changed_line_bg);
+    }
+
+    #[test]
+    fn test_search_and_cycle_matches() {
+        let lines = lines(30);
+        let mut state = MarkdownViewState::new();
+        state.viewport_height = 5;
+
+        assert_eq!(
+            state.search(&lines, "line 2", SearchOptions::default()),
+            Ok(11)
+        );
+        assert_eq!(state.current_match().map(|m| m.line), Some(2));
+        state.prev_match();
+        assert_eq!(state.current_match().map(|m| m.line), Some(29));
+        assert!(state.scroll <= 29 && state.scroll + 5 > 29);
+        state.next_match();
+        assert_eq!(state.current_match().map(|m| m.line), Some(2));
+
+        assert!(
+            state
+                .search(
+                    &lines,
+                    "(",
+                    SearchOptions {
+                        regex: true,
+                        whole_word: false
+                    }
+                )
+                .is_err()
+        );
+        assert_eq!(state.search(&lines, "", SearchOptions::default()), Ok(0));
+        assert!(state.current_match().is_none());
+    }
+}
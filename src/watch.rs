@@ -0,0 +1,334 @@
+use crate::events::{Waker, Worker};
+use notify::{Event, EventKind, PollWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+/// Quiet period after the last event before a file is reported as changed,
+/// so editors that write in several steps trigger a single reload
+pub const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// How often the polling fallback checks watched directories
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A watched file that settled after changing on disk
+#[derive(Debug, PartialEq, Eq)]
+pub enum FileChange {
+    Modified(PathBuf),
+    Deleted(PathBuf),
+}
+
+/// Key identifying a watched file: its canonical directory plus file name.
+/// Works for files that no longer exist, and matches the paths in watcher events.
+pub fn watch_key(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    match (absolute.parent(), absolute.file_name()) {
+        (Some(dir), Some(name)) => dir
+            .canonicalize()
+            .unwrap_or_else(|_| dir.to_path_buf())
+            .join(name),
+        _ => absolute,
+    }
+}
+
+/// Collects event times per path and releases paths once they go quiet
+#[derive(Debug, Default)]
+pub struct Debouncer {
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl Debouncer {
+    /// Record an event for `path` at `now`
+    pub fn touch(&mut self, path: PathBuf, now: Instant) {
+        self.pending.insert(path, now);
+    }
+
+    /// Take the paths with no events for at least `DEBOUNCE`, sorted
+    pub fn ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        let mut ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, last)| now.duration_since(**last) >= DEBOUNCE)
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready.sort();
+        for path in &ready {
+            self.pending.remove(path);
+        }
+        ready
+    }
+
+    /// Drop any pending event for `path`
+    pub fn forget(&mut self, path: &Path) {
+        self.pending.remove(path);
+    }
+
+    /// When the next pending path goes quiet
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().min().map(|&last| last + DEBOUNCE)
+    }
+}
+
+/// Turns watcher events into settled changes of the watched files
+#[derive(Debug, Default)]
+pub struct ChangeTracker {
+    files: HashSet<PathBuf>,
+    debouncer: Debouncer,
+}
+
+impl ChangeTracker {
+    /// Track changes to the file with watch key `key`
+    pub fn add(&mut self, key: PathBuf) {
+        self.files.insert(key);
+    }
+
+    /// Stop tracking the file with watch key `key`
+    pub fn remove(&mut self, key: &Path) {
+        self.files.remove(key);
+        self.debouncer.forget(key);
+    }
+
+    /// Whether any tracked file is in `dir`
+    pub fn has_files_in(&self, dir: &Path) -> bool {
+        self.files.iter().any(|file| file.parent() == Some(dir))
+    }
+
+    /// Record an event at `now`. A rename names both the old and new path,
+    /// so a file replaced by renaming a temporary file over it is caught.
+    pub fn record(&mut self, event: Event, now: Instant) {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths {
+            if self.files.contains(&path) {
+                self.debouncer.touch(path, now);
+            }
+        }
+    }
+
+    /// Tracked files that went quiet by `now`, as modified or deleted
+    /// depending on whether they still exist
+    pub fn settled(&mut self, now: Instant) -> Vec<FileChange> {
+        self.debouncer
+            .ready(now)
+            .into_iter()
+            .map(|path| {
+                if path.exists() {
+                    FileChange::Modified(path)
+                } else {
+                    FileChange::Deleted(path)
+                }
+            })
+            .collect()
+    }
+
+    /// When `settled` next has something to release
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.debouncer.next_deadline()
+    }
+}
+
+/// Watches open documents for changes.
+///
+/// The parent directory of each file is watched rather than the file itself,
+/// so editors that save by writing a temporary file and renaming it over the
+/// original keep being tracked. Uses inotify (or the platform equivalent) and
+/// falls back to polling when that is unavailable.
+pub struct FileWatcher {
+    watcher: Box<dyn Watcher + Send>,
+    receiver: Receiver<notify::Result<Event>>,
+    dirs: HashSet<PathBuf>,
+    tracker: ChangeTracker,
+}
+
+impl FileWatcher {
+    /// Create a watcher whose events wake the main loop through `waker`
+    pub fn new(waker: &Waker) -> Option<Self> {
+        let (tx, receiver) = waker.channel(Worker::FileWatch);
+        let tx = std::sync::Arc::new(tx);
+        let handler = {
+            let tx = tx.clone();
+            move |event| {
+                let _ = tx.send(event);
+            }
+        };
+        let watcher: Box<dyn Watcher + Send> = match notify::recommended_watcher(handler) {
+            Ok(watcher) => Box::new(watcher),
+            Err(_) => {
+                let config = notify::Config::default().with_poll_interval(POLL_INTERVAL);
+                let handler = move |event| {
+                    let _ = tx.send(event);
+                };
+                Box::new(PollWatcher::new(handler, config).ok()?)
+            }
+        };
+
+        Some(Self {
+            watcher,
+            receiver,
+            dirs: HashSet::new(),
+            tracker: ChangeTracker::default(),
+        })
+    }
+
+    /// Start watching `path` (no-op if already watched)
+    pub fn watch(&mut self, path: &Path) {
+        let key = watch_key(path);
+        let Some(dir) = key.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        if !self.dirs.contains(&dir) {
+            if self
+                .watcher
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .is_err()
+            {
+                return;
+            }
+            self.dirs.insert(dir);
+        }
+        self.tracker.add(key);
+    }
+
+    /// Stop watching `path`, and its directory once no watched file is left in it
+    pub fn unwatch(&mut self, path: &Path) {
+        let key = watch_key(path);
+        self.tracker.remove(&key);
+        if let Some(dir) = key.parent()
+            && !self.tracker.has_files_in(dir)
+            && self.dirs.remove(dir)
+        {
+            let _ = self.watcher.unwatch(dir);
+        }
+    }
+
+    /// When `poll` next needs to run to release a settled file
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.tracker.next_deadline()
+    }
+
+    /// Drain pending events and return the watched files that settled since
+    /// the last call
+    pub fn poll(&mut self) -> Vec<FileChange> {
+        let now = Instant::now();
+        while let Ok(result) = self.receiver.try_recv() {
+            if let Ok(event) = result {
+                self.tracker.record(event, now);
+            }
+        }
+        self.tracker.settled(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debouncer_waits_for_quiet_period() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::default();
+        debouncer.touch(PathBuf::from("/a.md"), start);
+        debouncer.touch(PathBuf::from("/b.md"), start + DEBOUNCE / 2);
+
+        assert_eq!(debouncer.next_deadline(), Some(start + DEBOUNCE));
+        assert!(debouncer.ready(start + DEBOUNCE / 2).is_empty());
+        assert_eq!(
+            debouncer.ready(start + DEBOUNCE),
+            vec![PathBuf::from("/a.md")]
+        );
+
+        // A new event restarts the quiet period
+        debouncer.touch(PathBuf::from("/b.md"), start + DEBOUNCE);
+        assert!(debouncer.ready(start + DEBOUNCE * 3 / 2).is_empty());
+        assert_eq!(
+            debouncer.ready(start + DEBOUNCE * 2),
+            vec![PathBuf::from("/b.md")]
+        );
+        assert!(debouncer.ready(start + DEBOUNCE * 10).is_empty());
+    }
+
+    #[test]
+    fn test_watch_key_handles_missing_files() {
+        let dir = std::env::temp_dir().canonicalize().unwrap();
+        let key = watch_key(&dir.join("barkdocs-missing-file.md"));
+        assert_eq!(key, dir.join("barkdocs-missing-file.md"));
+        assert_eq!(
+            watch_key(Path::new("README.md")).file_name().unwrap(),
+            "README.md"
+        );
+        assert!(watch_key(Path::new("README.md")).is_absolute());
+    }
+
+    #[test]
+    fn test_tracker_reports_atomic_save() {
+        use notify::event::{AccessKind, CreateKind, ModifyKind, RenameMode};
+
+        let start = Instant::now();
+        let file = watch_key(Path::new("Cargo.toml"));
+        let temp = file.with_file_name(".Cargo.toml.swp");
+        let mut tracker = ChangeTracker::default();
+        tracker.add(file.clone());
+
+        // Save the way many editors do: write a temp file, rename it over the original
+        tracker.record(
+            Event::new(EventKind::Create(CreateKind::File)).add_path(temp.clone()),
+            start,
+        );
+        tracker.record(
+            Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+                .add_path(temp)
+                .add_path(file.clone()),
+            start + DEBOUNCE / 2,
+        );
+        assert_eq!(tracker.next_deadline(), Some(start + DEBOUNCE * 3 / 2));
+        assert!(tracker.settled(start + DEBOUNCE).is_empty());
+        assert_eq!(
+            tracker.settled(start + DEBOUNCE * 3 / 2),
+            vec![FileChange::Modified(file.clone())]
+        );
+
+        // Reads don't count as changes
+        tracker.record(
+            Event::new(EventKind::Access(AccessKind::Any)).add_path(file),
+            start + DEBOUNCE * 2,
+        );
+        assert_eq!(tracker.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_tracker_reports_deleted_and_forgets_removed_files() {
+        use notify::event::RemoveKind;
+
+        let start = Instant::now();
+        let dir = std::env::temp_dir().canonicalize().unwrap();
+        let missing = dir.join("barkdocs-missing-file.md");
+        let other = dir.join("barkdocs-other-file.md");
+        let mut tracker = ChangeTracker::default();
+        tracker.add(missing.clone());
+        tracker.add(other.clone());
+        assert!(tracker.has_files_in(&dir));
+
+        let removed =
+            |path: &PathBuf| Event::new(EventKind::Remove(RemoveKind::File)).add_path(path.clone());
+        tracker.record(removed(&missing), start);
+        tracker.record(removed(&other), start);
+        tracker.remove(&other);
+        assert_eq!(
+            tracker.settled(start + DEBOUNCE),
+            vec![FileChange::Deleted(missing.clone())]
+        );
+
+        // Untracked files are ignored
+        tracker.record(removed(&other), start + DEBOUNCE);
+        assert!(tracker.settled(start + DEBOUNCE * 2).is_empty());
+
+        tracker.remove(&missing);
+        assert!(!tracker.has_files_in(&dir));
+    }
+}